@@ -0,0 +1,40 @@
+use chrono::{Duration, Utc};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use portfolio::models::Metric;
+use portfolio::storage::MetricStorage;
+
+const HISTORY_SIZE: i64 = 100_000;
+
+fn populate(storage: &MetricStorage) {
+    let start = Utc::now() - Duration::seconds(HISTORY_SIZE);
+    for i in 0..HISTORY_SIZE {
+        storage
+            .add(Metric::with_timestamp(
+                "requests",
+                i as f64,
+                start + Duration::seconds(i),
+            ))
+            .unwrap();
+    }
+}
+
+fn bench_time_range(c: &mut Criterion) {
+    let scan_storage = MetricStorage::new();
+    let sorted_storage = MetricStorage::new().with_sorted_inserts(true);
+    populate(&scan_storage);
+    populate(&sorted_storage);
+
+    let end = Utc::now();
+    let start = end - Duration::minutes(5);
+
+    c.bench_function("metric_time_range_scan", |b| {
+        b.iter(|| scan_storage.get_by_time_range(black_box(start), black_box(end)))
+    });
+
+    c.bench_function("metric_time_range_sorted", |b| {
+        b.iter(|| sorted_storage.get_by_time_range(black_box(start), black_box(end)))
+    });
+}
+
+criterion_group!(benches, bench_time_range);
+criterion_main!(benches);
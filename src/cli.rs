@@ -0,0 +1,37 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+// Command-line flags. Every flag is optional and, when given, overrides the
+// value loaded from the environment (see `Config::apply_cli`).
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "portfolio", about = "A static website generator")]
+pub struct CliArgs {
+    #[arg(long, help = "Seconds between generation runs")]
+    pub interval: Option<u64>,
+
+    #[arg(long, help = "Directory the generated site is written to")]
+    pub output_dir: Option<PathBuf>,
+
+    #[arg(long, help = "Directory templates are loaded from")]
+    pub templates_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Generate the site once and exit instead of running the scheduler"
+    )]
+    pub once: bool,
+
+    #[arg(
+        long,
+        requires = "once",
+        help = "With --once, print a JSON report of the task results to stdout"
+    )]
+    pub report_json: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Env-style file of configuration variables, loaded before the environment"
+    )]
+    pub config: Option<PathBuf>,
+}
@@ -1,6 +1,10 @@
+use crate::cli::CliArgs;
 use crate::error::{Error, Result};
+use crate::models::LogLevel;
+use crate::templating::html_renderer::{DEFAULT_CHART_HEIGHT, DEFAULT_CHART_WIDTH};
+use crate::templating::text_renderer::DEFAULT_TERMINAL_WIDTH;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -21,6 +25,39 @@ pub struct Config {
 
     #[serde(default = "default_verbose")]
     pub verbose: bool,
+
+    #[serde(default = "default_gzip_output")]
+    pub gzip_output: bool,
+
+    #[serde(default = "default_text_width")]
+    pub text_width: usize,
+
+    #[serde(default = "default_text_ascii_only")]
+    pub text_ascii_only: bool,
+
+    #[serde(default = "default_chart_width")]
+    pub chart_width: u32,
+
+    #[serde(default = "default_chart_height")]
+    pub chart_height: u32,
+
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+
+    #[serde(default = "default_min_log_level")]
+    pub min_log_level: String,
+
+    // Unset renders every trace in the window.
+    #[serde(default)]
+    pub max_traces_rendered: Option<usize>,
+
+    // Unset means "seed in debug builds only", see `should_seed`.
+    #[serde(default)]
+    pub seed_sample_data: Option<bool>,
+
+    // Copied verbatim into `output_dir` on every run when set.
+    #[serde(default)]
+    pub static_dir: Option<PathBuf>,
 }
 
 fn default_source_dir() -> PathBuf {
@@ -47,7 +84,64 @@ fn default_verbose() -> bool {
     false
 }
 
+fn default_gzip_output() -> bool {
+    false
+}
+
+fn default_text_width() -> usize {
+    DEFAULT_TERMINAL_WIDTH
+}
+
+fn default_text_ascii_only() -> bool {
+    false
+}
+
+fn default_chart_width() -> u32 {
+    DEFAULT_CHART_WIDTH
+}
+
+fn default_chart_height() -> u32 {
+    DEFAULT_CHART_HEIGHT
+}
+
+fn default_dry_run() -> bool {
+    false
+}
+
+fn default_min_log_level() -> String {
+    "DEBUG".to_string()
+}
+
+// dotenv never overwrites a variable that is already set, so the `--config`
+// file is loaded before `.env` to take precedence over it. Without an explicit
+// `dotenv_file`, `.env` is searched for in the working directory and its parents.
+pub fn load_env_files(config_file: Option<&Path>, dotenv_file: Option<&Path>) -> Result<()> {
+    if let Some(config_file) = config_file {
+        dotenv::from_path(config_file).map_err(|e| {
+            Error::ConfigError(format!(
+                "Failed to load config file {:?}: {}",
+                config_file, e
+            ))
+        })?;
+    }
+
+    // A missing `.env` is fine; it is optional.
+    let _ = match dotenv_file {
+        Some(dotenv_file) => dotenv::from_path(dotenv_file),
+        None => dotenv::dotenv().map(|_| ()),
+    };
+    Ok(())
+}
+
+pub fn should_seed(config: &Config, debug_build: bool) -> bool {
+    config.seed_sample_data.unwrap_or(debug_build)
+}
+
 impl Config {
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+
     pub fn from_env() -> Result<Self> {
         match envy::from_env::<Config>() {
             Ok(config) => Ok(config),
@@ -65,18 +159,37 @@ impl Config {
         }
     }
 
-    pub fn default() -> Self {
-        Self {
-            source_dir: default_source_dir(),
-            output_dir: default_output_dir(),
-            templates_dir: default_templates_dir(),
-            interval_seconds: default_interval(),
-            workers: default_workers(),
-            verbose: default_verbose(),
+    // Command-line flags take precedence over everything loaded from the environment.
+    pub fn apply_cli(&mut self, args: &CliArgs) {
+        if let Some(interval) = args.interval {
+            self.interval_seconds = interval;
+        }
+
+        if let Some(output_dir) = &args.output_dir {
+            self.output_dir = output_dir.clone();
+        }
+
+        if let Some(templates_dir) = &args.templates_dir {
+            self.templates_dir = templates_dir.clone();
         }
     }
 
+    pub fn log_level_floor(&self) -> Result<LogLevel> {
+        LogLevel::from_str(&self.min_log_level).ok_or_else(|| {
+            Error::ConfigError(format!("Invalid min_log_level '{}'", self.min_log_level))
+        })
+    }
+
     pub fn validate(&self) -> Result<()> {
+        self.log_level_floor()?;
+
+        if self.text_width < 20 {
+            return Err(Error::ConfigError(format!(
+                "text_width must be at least 20, got {}",
+                self.text_width
+            )));
+        }
+
         if !self.source_dir.exists() {
             println!("Creating source directory: {:?}", self.source_dir);
             std::fs::create_dir_all(&self.source_dir).map_err(|e| {
@@ -102,11 +215,156 @@ impl Config {
     }
 }
 
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            source_dir: default_source_dir(),
+            output_dir: default_output_dir(),
+            templates_dir: default_templates_dir(),
+            interval_seconds: default_interval(),
+            workers: default_workers(),
+            verbose: default_verbose(),
+            gzip_output: default_gzip_output(),
+            text_width: default_text_width(),
+            text_ascii_only: default_text_ascii_only(),
+            chart_width: default_chart_width(),
+            chart_height: default_chart_height(),
+            dry_run: default_dry_run(),
+            min_log_level: default_min_log_level(),
+            max_traces_rendered: None,
+            seed_sample_data: None,
+            static_dir: None,
+        }
+    }
+}
+
+// For embedders that configure the generator in code rather than through the
+// environment. Unset fields keep their defaults; `build` validates the result.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_source_dir<P: Into<PathBuf>>(mut self, source_dir: P) -> Self {
+        self.config.source_dir = source_dir.into();
+        self
+    }
+
+    pub fn with_output_dir<P: Into<PathBuf>>(mut self, output_dir: P) -> Self {
+        self.config.output_dir = output_dir.into();
+        self
+    }
+
+    pub fn with_templates_dir<P: Into<PathBuf>>(mut self, templates_dir: P) -> Self {
+        self.config.templates_dir = templates_dir.into();
+        self
+    }
+
+    pub fn with_interval_seconds(mut self, interval_seconds: u64) -> Self {
+        self.config.interval_seconds = interval_seconds;
+        self
+    }
+
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.config.workers = workers;
+        self
+    }
+
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.config.verbose = verbose;
+        self
+    }
+
+    pub fn with_gzip_output(mut self, gzip_output: bool) -> Self {
+        self.config.gzip_output = gzip_output;
+        self
+    }
+
+    pub fn with_text_width(mut self, text_width: usize) -> Self {
+        self.config.text_width = text_width;
+        self
+    }
+
+    pub fn with_text_ascii_only(mut self, ascii_only: bool) -> Self {
+        self.config.text_ascii_only = ascii_only;
+        self
+    }
+
+    pub fn with_chart_size(mut self, width: u32, height: u32) -> Self {
+        self.config.chart_width = width;
+        self.config.chart_height = height;
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.config.dry_run = dry_run;
+        self
+    }
+
+    pub fn with_min_log_level(mut self, min_log_level: &str) -> Self {
+        self.config.min_log_level = min_log_level.to_string();
+        self
+    }
+
+    pub fn with_max_traces_rendered(mut self, max_traces: Option<usize>) -> Self {
+        self.config.max_traces_rendered = max_traces;
+        self
+    }
+
+    pub fn with_seed_sample_data(mut self, seed: bool) -> Self {
+        self.config.seed_sample_data = Some(seed);
+        self
+    }
+
+    pub fn with_static_dir<P: Into<PathBuf>>(mut self, static_dir: P) -> Self {
+        self.config.static_dir = Some(static_dir.into());
+        self
+    }
+
+    // Runs `Config::validate`, which also creates any missing directories.
+    pub fn build(self) -> Result<Config> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use clap::Parser;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_apply_cli() {
+        let mut config = Config::default();
+        config.apply_cli(&CliArgs::default());
+        assert_eq!(config.interval_seconds, 30);
+        assert_eq!(config.output_dir, PathBuf::from("./public"));
+
+        let args = CliArgs::try_parse_from([
+            "portfolio",
+            "--interval",
+            "5",
+            "--output-dir",
+            "/tmp/site",
+            "--templates-dir",
+            "/tmp/templates",
+            "--once",
+        ])
+        .unwrap();
+        config.apply_cli(&args);
+
+        assert!(args.once);
+        assert_eq!(config.interval_seconds, 5);
+        assert_eq!(config.output_dir, PathBuf::from("/tmp/site"));
+        assert_eq!(config.templates_dir, PathBuf::from("/tmp/templates"));
+    }
+
     #[test]
     fn test_default_config() {
         let config = Config::default();
@@ -130,11 +388,110 @@ mod tests {
             interval_seconds: 30,
             workers: 2,
             verbose: false,
+            gzip_output: false,
+            text_width: 80,
+            text_ascii_only: false,
+            chart_width: 600,
+            chart_height: 200,
+            dry_run: false,
+            min_log_level: "warning".to_string(),
+            max_traces_rendered: None,
+            seed_sample_data: None,
+            static_dir: None,
         };
 
         assert!(config.validate().is_ok());
         assert!(config.source_dir.exists());
         assert!(config.templates_dir.exists());
         assert!(config.output_dir.exists());
+
+        assert_eq!(config.log_level_floor().unwrap(), LogLevel::Warning);
+
+        let bad_level = Config {
+            min_log_level: "verbose".to_string(),
+            ..config.clone()
+        };
+        assert!(bad_level.validate().is_err());
+
+        let narrow = Config {
+            text_width: 19,
+            ..config
+        };
+        assert!(narrow.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_builder() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Directories point at a temp dir only because `build` creates them.
+        let config = Config::builder()
+            .with_interval_seconds(5)
+            .with_source_dir(temp_dir.path().join("content"))
+            .with_templates_dir(temp_dir.path().join("templates"))
+            .with_output_dir(temp_dir.path().join("public"))
+            .build()
+            .unwrap();
+
+        assert_eq!(config.interval_seconds, 5);
+        assert_eq!(config.workers, default_workers());
+        assert_eq!(config.text_width, DEFAULT_TERMINAL_WIDTH);
+        assert_eq!(config.chart_width, DEFAULT_CHART_WIDTH);
+        assert_eq!(config.min_log_level, "DEBUG");
+        assert!(!config.dry_run);
+        assert_eq!(config.static_dir, None);
+        assert!(config.output_dir.exists());
+
+        let invalid = Config::builder()
+            .with_source_dir(temp_dir.path().join("content"))
+            .with_templates_dir(temp_dir.path().join("templates"))
+            .with_output_dir(temp_dir.path().join("public"))
+            .with_text_width(10)
+            .build();
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_config_file_beats_dotenv() {
+        let dir = TempDir::new().unwrap();
+        let dotenv_file = dir.path().join(".env");
+        let config_file = dir.path().join("portfolio.env");
+        std::fs::write(
+            &dotenv_file,
+            "PORTFOLIO_TEST_PRECEDENCE=dotenv\nPORTFOLIO_TEST_DOTENV_ONLY=dotenv\n",
+        )
+        .unwrap();
+        std::fs::write(&config_file, "PORTFOLIO_TEST_PRECEDENCE=config\n").unwrap();
+
+        load_env_files(Some(&config_file), Some(&dotenv_file)).unwrap();
+
+        assert_eq!(
+            std::env::var("PORTFOLIO_TEST_PRECEDENCE").unwrap(),
+            "config"
+        );
+        assert_eq!(
+            std::env::var("PORTFOLIO_TEST_DOTENV_ONLY").unwrap(),
+            "dotenv"
+        );
+        assert!(load_env_files(Some(&dir.path().join("missing.env")), None).is_err());
+    }
+
+    #[test]
+    fn test_should_seed() {
+        let config = Config::default();
+        assert!(should_seed(&config, true));
+        assert!(!should_seed(&config, false));
+
+        let enabled = Config {
+            seed_sample_data: Some(true),
+            ..Config::default()
+        };
+        assert!(should_seed(&enabled, false));
+
+        let disabled = Config {
+            seed_sample_data: Some(false),
+            ..Config::default()
+        };
+        assert!(!should_seed(&disabled, true));
     }
 }
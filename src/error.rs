@@ -17,6 +17,13 @@ pub enum Error {
     #[error("Template error: {0}")]
     TemplateError(#[from] tera::Error),
 
+    #[error("Render error in template '{template}' ({block_kind} block): {message}")]
+    RenderError {
+        template: String,
+        block_kind: String,
+        message: String,
+    },
+
     #[error("Scheduler error: {0}")]
     SchedulerError(String),
 
@@ -1,7 +1,9 @@
+pub mod cli;
 pub mod config;
 pub mod error;
 pub mod models;
 pub mod scheduler;
+pub mod shutdown;
 pub mod storage;
 pub mod tasks;
 pub mod templating;
@@ -1,25 +1,35 @@
+use clap::Parser;
 use log::{error, info};
 use portfolio::{
-    config::Config,
-    error::Result,
+    cli::CliArgs,
+    config::{load_env_files, should_seed, Config},
+    error::{Error, Result},
     models::{LogEntry, LogLevel, Metric, Trace},
     scheduler::Scheduler,
+    shutdown::shutdown_signal,
     storage::{LogStorage, MetricStorage, TraceStorage},
-    tasks::home_generator::HomeGeneratorTask,
+    tasks::{home_generator::HomeGeneratorTask, static_assets::StaticAssetsTask},
     templating::TemplateEngine,
 };
 use std::collections::HashMap;
 use std::{process, sync::Arc};
-use tokio::signal;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    dotenv::dotenv().ok();
+    let args = CliArgs::parse();
+
+    // Env files are loaded before the logger so `RUST_LOG` can come from them.
+    let env_files = load_env_files(args.config.as_deref(), None);
     env_logger::init();
 
     info!("Starting portfolio website generator...");
 
-    let config = match Config::from_env() {
+    if let Err(e) = env_files {
+        error!("{}", e);
+        process::exit(1);
+    }
+
+    let mut config = match Config::from_env() {
         Ok(config) => {
             info!("Configuration loaded successfully");
             config
@@ -30,40 +40,68 @@ async fn main() -> Result<()> {
         }
     };
 
+    config.apply_cli(&args);
     config.validate()?;
 
     let metric_storage = Arc::new(MetricStorage::new());
     let trace_storage = Arc::new(TraceStorage::new());
     let log_storage = Arc::new(LogStorage::new());
 
-    add_sample_data(&metric_storage, &trace_storage, &log_storage)?;
+    if should_seed(&config, cfg!(debug_assertions)) {
+        add_sample_data(&metric_storage, &trace_storage, &log_storage)?;
+    }
 
-    let template_engine = Arc::new(TemplateEngine::new(&config.templates_dir));
+    let template_engine =
+        Arc::new(TemplateEngine::new(&config.templates_dir).with_gzip_output(config.gzip_output));
 
     let scheduler = Scheduler::new(config.clone());
 
-    let home_generator_task = Arc::new(HomeGeneratorTask::new(
-        template_engine,
-        metric_storage,
-        trace_storage,
-        log_storage,
-        config.output_dir.to_string_lossy().into_owned(),
-    ));
+    if let Some(static_dir) = &config.static_dir {
+        let static_assets_task = StaticAssetsTask::new(static_dir, &config.output_dir);
+        scheduler.add_task(Arc::new(static_assets_task)).await?;
+    }
 
-    scheduler.add_task(home_generator_task).await;
+    let home_generator_task = Arc::new(
+        HomeGeneratorTask::builder()
+            .with_template_engine(template_engine)
+            .with_metric_storage(metric_storage)
+            .with_trace_storage(trace_storage)
+            .with_log_storage(log_storage)
+            .with_output_dir(&config.output_dir.to_string_lossy())
+            .with_text_width(config.text_width)
+            .with_text_ascii_only(config.text_ascii_only)
+            .with_chart_size(config.chart_width, config.chart_height)
+            .with_dry_run(config.dry_run)
+            .with_min_log_level(config.log_level_floor()?)
+            .with_max_traces_rendered(config.max_traces_rendered)
+            .build()?,
+    );
+
+    scheduler.add_task(home_generator_task).await?;
+
+    if args.once {
+        let report = scheduler.run_once().await?;
+        if args.report_json {
+            println!("{}", report.to_json()?);
+        }
+        if !report.succeeded() {
+            return Err(Error::SchedulerError(format!(
+                "Tasks failed: {}",
+                report.failed_tasks().join(", ")
+            )));
+        }
+        info!("Site generated once, exiting");
+        return Ok(());
+    }
 
     match scheduler.run().await {
         Ok(_) => {
             info!("Scheduler started successfully");
 
-            match signal::ctrl_c().await {
-                Ok(()) => {
-                    info!("Shutdown signal received, stopping scheduler...");
-                    scheduler.stop().await?;
-                    info!("Scheduler stopped gracefully");
-                }
-                Err(e) => error!("Error waiting for shutdown signal: {}", e),
-            }
+            shutdown_signal().await;
+            info!("Shutdown signal received, stopping scheduler...");
+            scheduler.stop().await?;
+            info!("Scheduler stopped gracefully");
 
             Ok(())
         }
@@ -1,9 +1,13 @@
-use chrono::{DateTime, Utc};
+use crate::error::Error;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum LogLevel {
     Debug,
     Info,
@@ -21,6 +25,7 @@ impl LogLevel {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_uppercase().as_str() {
             "DEBUG" => Some(LogLevel::Debug),
@@ -94,7 +99,7 @@ impl LogEntry {
     pub fn format(&self) -> String {
         format!(
             "[{}] [{}] [{}]: {}",
-            self.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
+            self.timestamp.format(TIMESTAMP_FORMAT),
             self.level,
             self.source,
             self.message
@@ -104,6 +109,37 @@ impl LogEntry {
     pub fn is_level_at_least(&self, level: LogLevel) -> bool {
         self.level >= level
     }
+
+    // Single-line JSON without the trailing newline, suitable for NDJSON files.
+    pub fn to_json_line(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format())
+    }
+}
+
+impl FromStr for LogEntry {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Unknown(format!("Invalid log entry '{}'", s));
+
+        let rest = s.strip_prefix('[').ok_or_else(invalid)?;
+        let (timestamp, rest) = rest.split_once("] [").ok_or_else(invalid)?;
+        let (level, rest) = rest.split_once("] [").ok_or_else(invalid)?;
+        let (source, message) = rest.split_once("]: ").ok_or_else(invalid)?;
+
+        let timestamp = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+            .map_err(|_| invalid())?
+            .and_utc();
+        let level = LogLevel::from_str(level).ok_or_else(invalid)?;
+
+        Ok(LogEntry::with_timestamp(message, level, source, timestamp))
+    }
 }
 
 #[cfg(test)]
@@ -228,4 +264,30 @@ mod tests {
         assert!(error_log.is_level_at_least(LogLevel::Warning));
         assert!(error_log.is_level_at_least(LogLevel::Error));
     }
+
+    #[test]
+    fn test_display_from_str_round_trip() {
+        let timestamp = Utc.with_ymd_and_hms(2025, 3, 8, 12, 0, 0).unwrap()
+            + chrono::Duration::milliseconds(123);
+        let log = LogEntry::with_timestamp(
+            "Connection refused: [db-1] retrying",
+            LogLevel::Warning,
+            "database",
+            timestamp,
+        )
+        .with_metadata("attempt", "3");
+
+        let line = log.to_string();
+        assert_eq!(line, log.format());
+
+        let parsed = LogEntry::from_str(&line).unwrap();
+        assert_eq!(parsed.message, log.message);
+        assert_eq!(parsed.level, log.level);
+        assert_eq!(parsed.source, log.source);
+        assert_eq!(parsed.timestamp, log.timestamp);
+        assert!(parsed.metadata.is_empty());
+
+        assert!(LogEntry::from_str("not a log line").is_err());
+        assert!(LogEntry::from_str("[2025-03-08 12:00:00.123] [LOUD] [app]: hi").is_err());
+    }
 }
@@ -8,6 +8,8 @@ pub struct Metric {
     pub value: f64,
     pub timestamp: DateTime<Utc>,
     pub labels: HashMap<String, String>,
+    #[serde(default)]
+    pub unit: Option<String>,
 }
 
 impl Metric {
@@ -17,6 +19,7 @@ impl Metric {
             value,
             timestamp: Utc::now(),
             labels: HashMap::new(),
+            unit: None,
         }
     }
 
@@ -26,9 +29,15 @@ impl Metric {
             value,
             timestamp,
             labels: HashMap::new(),
+            unit: None,
         }
     }
 
+    pub fn with_unit(mut self, unit: &str) -> Self {
+        self.unit = Some(unit.to_string());
+        self
+    }
+
     pub fn with_label(mut self, key: &str, value: &str) -> Self {
         self.labels.insert(key.to_string(), value.to_string());
         self
@@ -53,6 +62,45 @@ impl Metric {
             None => false,
         }
     }
+
+    // Falls back to the older `unit` label for metrics recorded before the field existed.
+    pub fn unit(&self) -> Option<&str> {
+        self.unit
+            .as_deref()
+            .or_else(|| self.get_label("unit").map(|unit| unit.as_str()))
+    }
+
+    pub fn label_as_f64(&self, key: &str) -> Option<f64> {
+        self.get_label(key).and_then(|value| parse_f64(value))
+    }
+
+    pub fn label_as_i64(&self, key: &str) -> Option<i64> {
+        self.get_label(key)
+            .and_then(|value| value.trim().parse::<i64>().ok())
+    }
+
+    pub fn trend(&self) -> Option<f64> {
+        self.get_label("trend").and_then(|t| parse_trend(t))
+    }
+}
+
+// Accepts surrounding whitespace and an explicit "+" sign; rejects NaN and infinities.
+fn parse_f64(s: &str) -> Option<f64> {
+    s.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+pub fn parse_trend(s: &str) -> Option<f64> {
+    let s = s.trim();
+
+    match s.to_lowercase().as_str() {
+        "up" => Some(1.0),
+        "down" => Some(-1.0),
+        "flat" => Some(0.0),
+        _ => parse_f64(s),
+    }
 }
 
 #[cfg(test)]
@@ -68,6 +116,18 @@ mod tests {
         assert!(metric.labels.is_empty());
     }
 
+    #[test]
+    fn test_explicit_unit_wins_over_label() {
+        let metric = Metric::new("cpu_usage", 85.5)
+            .with_label("unit", "percent")
+            .with_unit("%");
+        assert_eq!(metric.unit(), Some("%"));
+
+        let legacy = Metric::new("cpu_usage", 85.5).with_label("unit", "percent");
+        assert_eq!(legacy.unit(), Some("percent"));
+        assert_eq!(Metric::new("cpu_usage", 85.5).unit(), None);
+    }
+
     #[test]
     fn test_with_timestamp() {
         let timestamp = Utc.with_ymd_and_hms(2025, 3, 8, 12, 0, 0).unwrap();
@@ -111,4 +171,38 @@ mod tests {
         assert!(metric.has_label_value("method", "GET"));
         assert!(!metric.has_label_value("status", "404"));
     }
+
+    #[test]
+    fn test_label_as_number() {
+        let metric = Metric::new("requests", 120.0)
+            .with_label("trend", " +2.3 ")
+            .with_label("replicas", "+3")
+            .with_label("region", "us-west-1");
+
+        assert_eq!(metric.label_as_f64("trend"), Some(2.3));
+        assert_eq!(metric.label_as_i64("replicas"), Some(3));
+        assert_eq!(metric.label_as_f64("replicas"), Some(3.0));
+
+        assert_eq!(metric.label_as_f64("region"), None);
+        assert_eq!(metric.label_as_i64("region"), None);
+        assert_eq!(metric.label_as_i64("trend"), None);
+
+        assert_eq!(metric.label_as_f64("missing"), None);
+        assert_eq!(metric.label_as_i64("missing"), None);
+    }
+
+    #[test]
+    fn test_parse_trend() {
+        assert_eq!(parse_trend("+2.3"), Some(2.3));
+        assert_eq!(parse_trend("2.3"), Some(2.3));
+        assert_eq!(parse_trend("-0.5"), Some(-0.5));
+        assert_eq!(parse_trend("up"), Some(1.0));
+        assert_eq!(parse_trend("Down"), Some(-1.0));
+        assert_eq!(parse_trend(" flat "), Some(0.0));
+        assert_eq!(parse_trend("sideways"), None);
+        assert_eq!(parse_trend("NaN"), None);
+
+        let metric = Metric::new("cpu_usage", 85.5).with_label("trend", "up");
+        assert_eq!(metric.trend(), Some(1.0));
+    }
 }
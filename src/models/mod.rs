@@ -3,5 +3,5 @@ pub mod metric;
 pub mod trace;
 
 pub use log::{LogEntry, LogLevel};
-pub use metric::Metric;
+pub use metric::{parse_trend, Metric};
 pub use trace::Trace;
@@ -4,9 +4,13 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use futures::future::join_all;
 use log::{error, info, warn};
-use std::sync::Arc;
-use tokio::spawn;
-use tokio::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::runtime::Handle;
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
 use tokio::time;
 use tokio::time::Duration;
 
@@ -14,11 +18,16 @@ use tokio::time::Duration;
 pub trait Task: Send + Sync {
     fn name(&self) -> &str;
     async fn execute(&self) -> Result<()>;
+
+    fn depends_on(&self) -> Vec<&str> {
+        Vec::new()
+    }
 }
 
 pub struct ScheduledTask {
     task: Arc<dyn Task>,
     last_run: Option<DateTime<Utc>>,
+    last_run_succeeded: Option<bool>,
     success_count: usize,
     failure_count: usize,
 }
@@ -28,6 +37,7 @@ impl ScheduledTask {
         Self {
             task,
             last_run: None,
+            last_run_succeeded: None,
             success_count: 0,
             failure_count: 0,
         }
@@ -43,6 +53,7 @@ impl ScheduledTask {
             Ok(()) => {
                 self.success_count += 1;
                 self.last_run = Some(start_time);
+                self.last_run_succeeded = Some(true);
                 let duration = Utc::now() - start_time;
                 info!(
                     "Task '{}' completed successfully in {} ms",
@@ -54,6 +65,7 @@ impl ScheduledTask {
             Err(e) => {
                 self.failure_count += 1;
                 self.last_run = Some(start_time);
+                self.last_run_succeeded = Some(false);
                 let duration = Utc::now() - start_time;
                 error!(
                     "Task '{}' failed after {} ms: {}",
@@ -66,6 +78,13 @@ impl ScheduledTask {
         }
     }
 
+    fn record_failure(&mut self, err: &Error) {
+        self.failure_count += 1;
+        self.last_run = Some(Utc::now());
+        self.last_run_succeeded = Some(false);
+        error!("Task '{}' skipped: {}", self.task.name(), err);
+    }
+
     pub fn metrics(&self) -> TaskMetrics {
         TaskMetrics {
             name: self.task.name().to_string(),
@@ -76,7 +95,7 @@ impl ScheduledTask {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TaskMetrics {
     pub name: String,
     pub last_run: Option<DateTime<Utc>>,
@@ -84,10 +103,58 @@ pub struct TaskMetrics {
     pub failure_count: usize,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskRunResult {
+    pub name: String,
+    pub success: bool,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+// Outcome of a single `Scheduler::run_once`, serializable for CI tooling.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunReport {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub tasks: Vec<TaskRunResult>,
+}
+
+impl RunReport {
+    pub fn failed_tasks(&self) -> Vec<&str> {
+        self.tasks
+            .iter()
+            .filter(|task| !task.success)
+            .map(|task| task.name.as_str())
+            .collect()
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.tasks.iter().all(|task| task.success)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SchedulerHealth {
+    pub running: bool,
+    pub task_count: usize,
+    pub any_failing: bool,
+    pub oldest_last_run: Option<DateTime<Utc>>,
+}
+
 pub struct Scheduler {
     config: Config,
     tasks: Arc<Mutex<Vec<ScheduledTask>>>,
     running: Arc<Mutex<bool>>,
+    paused: Arc<AtomicBool>,
+    last_tick: Arc<Mutex<Option<DateTime<Utc>>>>,
+    // Copy of every task's metrics, refreshed after each run so it can be read
+    // from sync code without awaiting the task list lock.
+    metrics_snapshot: Arc<RwLock<Vec<TaskMetrics>>>,
+    loop_handle: Mutex<Option<(JoinHandle<()>, Arc<Notify>)>>,
 }
 
 impl Scheduler {
@@ -96,15 +163,30 @@ impl Scheduler {
             config,
             tasks: Arc::new(Mutex::new(Vec::new())),
             running: Arc::new(Mutex::new(false)),
+            paused: Arc::new(AtomicBool::new(false)),
+            last_tick: Arc::new(Mutex::new(None)),
+            metrics_snapshot: Arc::new(RwLock::new(Vec::new())),
+            loop_handle: Mutex::new(None),
         }
     }
 
-    pub async fn add_task(&self, task: Arc<dyn Task>) {
+    pub async fn add_task(&self, task: Arc<dyn Task>) -> Result<()> {
         let mut tasks = self.tasks.lock().await;
+
+        let mut graph: Vec<&dyn Task> = tasks.iter().map(|t| t.task.as_ref()).collect();
+        graph.push(task.as_ref());
+        dependency_waves(&graph)?;
+
         tasks.push(ScheduledTask::new(task));
+        publish_metrics(&tasks, &self.metrics_snapshot);
+        Ok(())
     }
 
     pub async fn run(&self) -> Result<()> {
+        self.run_on(Handle::current()).await
+    }
+
+    pub async fn run_on(&self, handle: Handle) -> Result<()> {
         let mut running = self.running.lock().await;
         if *running {
             return Err(Error::SchedulerError(
@@ -121,87 +203,213 @@ impl Scheduler {
         );
 
         let running_clone = self.running.clone();
+        let paused = self.paused.clone();
+        let last_tick = self.last_tick.clone();
         let tasks_clone = self.tasks.clone();
+        let metrics_snapshot = self.metrics_snapshot.clone();
+        let shutdown = Arc::new(Notify::new());
+        let shutdown_clone = shutdown.clone();
+
+        let loop_handle = handle.spawn(async move {
+            // Tick times follow the tokio clock from this anchor, so they step by
+            // exactly the interval.
+            let (started_at, started) = (Utc::now(), time::Instant::now());
+            let tick_time = |tick: time::Instant| {
+                started_at + chrono::Duration::from_std(tick - started).unwrap_or_default()
+            };
+            let mut interval = time::interval_at(started, Duration::from_secs(interval_seconds));
 
-        spawn(async move {
-            let mut interval = time::interval(Duration::from_secs(interval_seconds));
-
-            Self::execute_all_tasks(&tasks_clone).await;
+            *last_tick.lock().await = Some(started_at);
+            Self::execute_all_tasks(&tasks_clone, &metrics_snapshot).await;
 
             loop {
-                interval.tick().await;
+                let tick = tokio::select! {
+                    tick = interval.tick() => tick,
+                    _ = shutdown_clone.notified() => break,
+                };
 
                 let running = *running_clone.lock().await;
                 if !running {
                     break;
                 }
 
-                Self::execute_all_tasks(&tasks_clone).await;
+                *last_tick.lock().await = Some(tick_time(tick));
+                if paused.load(Ordering::SeqCst) {
+                    continue;
+                }
+
+                Self::execute_all_tasks(&tasks_clone, &metrics_snapshot).await;
             }
 
             info!("Scheduler stopped");
         });
+        *self.loop_handle.lock().await = Some((loop_handle, shutdown));
 
         Ok(())
     }
 
+    // While paused the loop keeps ticking but skips executing tasks.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    // None while stopped or paused, since no run is going to happen.
+    pub async fn next_run(&self) -> Option<DateTime<Utc>> {
+        if !*self.running.lock().await || self.is_paused() {
+            return None;
+        }
+
+        let last_tick = (*self.last_tick.lock().await)?;
+        Some(last_tick + chrono::Duration::seconds(self.config.interval_seconds as i64))
+    }
+
+    // Executes every task a single time without starting the interval loop.
+    // Task failures are recorded in the report rather than returned as errors.
+    pub async fn run_once(&self) -> Result<RunReport> {
+        let started_at = Utc::now();
+        let tasks = Self::execute_all_tasks(&self.tasks, &self.metrics_snapshot).await;
+
+        Ok(RunReport {
+            started_at,
+            finished_at: Utc::now(),
+            tasks,
+        })
+    }
+
     // This implementation intentionally acquires and releases the lock multiple times
     // to avoid holding it across await points, which could cause deadlocks.
     // Each task gets its own Arc clone and acquires the lock only when needed.
-    async fn execute_all_tasks(tasks: &Arc<Mutex<Vec<ScheduledTask>>>) {
-        let task_count = {
+    // Tasks run in dependency order: each wave only starts once the previous one finished.
+    async fn execute_all_tasks(
+        tasks: &Arc<Mutex<Vec<ScheduledTask>>>,
+        metrics_snapshot: &RwLock<Vec<TaskMetrics>>,
+    ) -> Vec<TaskRunResult> {
+        let waves = {
             let tasks_lock = tasks.lock().await;
             if tasks_lock.is_empty() {
                 warn!("No tasks to execute");
-                return;
+                return Vec::new();
+            }
+
+            let graph: Vec<&dyn Task> = tasks_lock.iter().map(|t| t.task.as_ref()).collect();
+            match dependency_waves(&graph) {
+                Ok(waves) => waves,
+                Err(e) => {
+                    error!("Cannot order tasks: {}", e);
+                    return Vec::new();
+                }
             }
-            tasks_lock.len()
         };
 
+        let task_count: usize = waves.iter().map(Vec::len).sum();
         info!("Executing {} tasks", task_count);
 
-        let mut handles = Vec::with_capacity(task_count);
-
-        for task_index in 0..task_count {
-            let tasks_clone = Arc::clone(tasks);
-
-            let task_future = async move {
-                let mut tasks_guard = tasks_clone.lock().await;
-
-                if let Some(task) = tasks_guard.get_mut(task_index) {
-                    let result = task.execute().await;
-                    (task_index, result)
-                } else {
-                    (
-                        task_index,
-                        Err(Error::SchedulerError("Task not found".to_string())),
-                    )
-                }
-            };
+        let mut failed: HashSet<String> = HashSet::new();
+        let mut success_count = 0;
+        let mut results = Vec::with_capacity(task_count);
+
+        for wave in waves {
+            let mut handles = Vec::with_capacity(wave.len());
+
+            for task_index in wave {
+                let tasks_clone = Arc::clone(tasks);
+                let failed = &failed;
+
+                let task_future = async move {
+                    let mut tasks_guard = tasks_clone.lock().await;
+
+                    let started = std::time::Instant::now();
+                    if let Some(task) = tasks_guard.get_mut(task_index) {
+                        let name = task.task.name().to_string();
+                        let failed_dependency = task
+                            .task
+                            .depends_on()
+                            .into_iter()
+                            .find(|dependency| failed.contains(*dependency))
+                            .map(str::to_string);
+
+                        let result = match failed_dependency {
+                            Some(dependency) => {
+                                let err = Error::SchedulerError(format!(
+                                    "Dependency '{}' of task '{}' failed",
+                                    dependency, name
+                                ));
+                                task.record_failure(&err);
+                                Err(err)
+                            }
+                            None => task.execute().await,
+                        };
+                        (name, result, started.elapsed())
+                    } else {
+                        (
+                            String::new(),
+                            Err(Error::SchedulerError("Task not found".to_string())),
+                            started.elapsed(),
+                        )
+                    }
+                };
+
+                handles.push(task_future);
+            }
 
-            handles.push(task_future);
+            for (name, result, elapsed) in join_all(handles).await {
+                let error = match result {
+                    Ok(()) => {
+                        success_count += 1;
+                        None
+                    }
+                    Err(e) => {
+                        failed.insert(name.clone());
+                        Some(e.to_string())
+                    }
+                };
+                results.push(TaskRunResult {
+                    name,
+                    success: error.is_none(),
+                    duration_ms: elapsed.as_millis() as u64,
+                    error,
+                });
+            }
         }
 
-        // Execute all tasks concurrently and collect results
-        let results = join_all(handles).await;
-
         // Log summary
-        let success_count = results.iter().filter(|(_, result)| result.is_ok()).count();
         info!(
             "Completed task execution: {}/{} successful",
             success_count, task_count
         );
+
+        publish_metrics(&tasks.lock().await, metrics_snapshot);
+        results
     }
 
+    // Waits for the scheduler loop to exit, so any in-flight tick finishes first.
     pub async fn stop(&self) -> Result<()> {
-        let mut running = self.running.lock().await;
-        if !*running {
-            return Err(Error::SchedulerError(
-                "Scheduler is not running".to_string(),
-            ));
+        {
+            let mut running = self.running.lock().await;
+            if !*running {
+                return Err(Error::SchedulerError(
+                    "Scheduler is not running".to_string(),
+                ));
+            }
+
+            *running = false;
+        }
+
+        if let Some((loop_handle, shutdown)) = self.loop_handle.lock().await.take() {
+            shutdown.notify_one();
+            loop_handle
+                .await
+                .map_err(|e| Error::SchedulerError(format!("Scheduler loop failed: {}", e)))?;
         }
 
-        *running = false;
         Ok(())
     }
 
@@ -209,6 +417,83 @@ impl Scheduler {
         let tasks = self.tasks.lock().await;
         tasks.iter().map(|task| task.metrics()).collect()
     }
+
+    // Same data as `metrics`, as of the end of the last run, without awaiting.
+    pub fn metrics_snapshot(&self) -> Vec<TaskMetrics> {
+        self.metrics_snapshot
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    pub async fn health(&self) -> SchedulerHealth {
+        let running = *self.running.lock().await;
+        let tasks = self.tasks.lock().await;
+
+        SchedulerHealth {
+            running,
+            task_count: tasks.len(),
+            any_failing: tasks
+                .iter()
+                .any(|task| task.last_run_succeeded == Some(false)),
+            oldest_last_run: tasks.iter().filter_map(|task| task.last_run).min(),
+        }
+    }
+}
+
+fn publish_metrics(tasks: &[ScheduledTask], snapshot: &RwLock<Vec<TaskMetrics>>) {
+    let metrics = tasks.iter().map(|task| task.metrics()).collect();
+    *snapshot
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = metrics;
+}
+
+// Groups tasks into waves where every task only depends on tasks from earlier waves.
+// Dependencies on unregistered task names are ignored.
+fn dependency_waves(tasks: &[&dyn Task]) -> Result<Vec<Vec<usize>>> {
+    let index: HashMap<&str, usize> = tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| (task.name(), i))
+        .collect();
+    let dependencies: Vec<Vec<usize>> = tasks
+        .iter()
+        .map(|task| {
+            task.depends_on()
+                .into_iter()
+                .filter_map(|dependency| index.get(dependency).copied())
+                .collect()
+        })
+        .collect();
+
+    let mut done = vec![false; tasks.len()];
+    let mut waves = Vec::new();
+    let mut scheduled = 0;
+
+    while scheduled < tasks.len() {
+        let wave: Vec<usize> = (0..tasks.len())
+            .filter(|&i| !done[i] && dependencies[i].iter().all(|&d| done[d]))
+            .collect();
+
+        if wave.is_empty() {
+            let stuck: Vec<&str> = (0..tasks.len())
+                .filter(|&i| !done[i])
+                .map(|i| tasks[i].name())
+                .collect();
+            return Err(Error::SchedulerError(format!(
+                "Dependency cycle detected among tasks: {}",
+                stuck.join(", ")
+            )));
+        }
+
+        for &i in &wave {
+            done[i] = true;
+        }
+        scheduled += wave.len();
+        waves.push(wave);
+    }
+
+    Ok(waves)
 }
 
 #[cfg(test)]
@@ -264,10 +549,311 @@ mod tests {
             counter: counter.clone(),
         };
 
-        scheduler.add_task(Arc::new(task)).await;
+        scheduler.add_task(Arc::new(task)).await.unwrap();
 
         let tasks = scheduler.tasks.lock().await;
         assert_eq!(tasks.len(), 1);
         assert_eq!(tasks[0].task.name(), "test_task");
     }
+
+    struct FailingTask;
+
+    #[async_trait]
+    impl Task for FailingTask {
+        fn name(&self) -> &str {
+            "failing_task"
+        }
+
+        async fn execute(&self) -> Result<()> {
+            Err(Error::GenerationError("boom".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_run_once() {
+        let scheduler = Scheduler::new(Config::default());
+        let counter = Arc::new(AtomicUsize::new(0));
+        scheduler
+            .add_task(Arc::new(TestTask {
+                name: "test_task".to_string(),
+                counter: counter.clone(),
+            }))
+            .await
+            .unwrap();
+
+        scheduler.run_once().await.unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(!scheduler.health().await.running);
+
+        scheduler.add_task(Arc::new(FailingTask)).await.unwrap();
+        let report = scheduler.run_once().await.unwrap();
+        assert_eq!(report.tasks.len(), 2);
+        assert!(!report.succeeded());
+        assert_eq!(report.failed_tasks(), vec!["failing_task"]);
+
+        let test_task = report.tasks.iter().find(|t| t.name == "test_task").unwrap();
+        assert!(test_task.success);
+        assert_eq!(test_task.error, None);
+        let failing = report
+            .tasks
+            .iter()
+            .find(|t| t.name == "failing_task")
+            .unwrap();
+        assert!(!failing.success);
+        assert!(failing.error.as_deref().unwrap().contains("boom"));
+        assert!(report.started_at <= report.finished_at);
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["tasks"][1]["success"], false);
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_metrics_snapshot() {
+        let scheduler = Scheduler::new(Config::default());
+        scheduler
+            .add_task(Arc::new(TestTask {
+                name: "test_task".to_string(),
+                counter: Arc::new(AtomicUsize::new(0)),
+            }))
+            .await
+            .unwrap();
+        scheduler.add_task(Arc::new(FailingTask)).await.unwrap();
+
+        let before = scheduler.metrics_snapshot();
+        assert_eq!(before.len(), 2);
+        assert!(before.iter().all(|m| m.last_run.is_none()));
+
+        let _ = scheduler.run_once().await;
+
+        let snapshot = scheduler.metrics_snapshot();
+        assert_eq!(snapshot, scheduler.metrics().await);
+        assert_eq!(snapshot[0].success_count, 1);
+        assert_eq!(snapshot[1].failure_count, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduler_next_run() {
+        let config = Config {
+            interval_seconds: 1,
+            ..Config::default()
+        };
+        let scheduler = Scheduler::new(config);
+        scheduler
+            .add_task(Arc::new(TestTask {
+                name: "test_task".to_string(),
+                counter: Arc::new(AtomicUsize::new(0)),
+            }))
+            .await
+            .unwrap();
+        assert!(scheduler.next_run().await.is_none());
+
+        scheduler.run().await.unwrap();
+        tokio::time::advance(Duration::from_millis(100)).await;
+        let first = scheduler.next_run().await.unwrap();
+        assert!(first > Utc::now());
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        tokio::task::yield_now().await;
+        let second = scheduler.next_run().await.unwrap();
+        assert_eq!(second - first, chrono::Duration::seconds(1));
+
+        scheduler.pause();
+        assert!(scheduler.next_run().await.is_none());
+        scheduler.resume();
+        assert!(scheduler.next_run().await.is_some());
+
+        scheduler.stop().await.unwrap();
+        assert!(scheduler.next_run().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_health() {
+        let scheduler = Scheduler::new(Config::default());
+
+        let health = scheduler.health().await;
+        assert!(!health.running);
+        assert_eq!(health.task_count, 0);
+        assert!(!health.any_failing);
+        assert!(health.oldest_last_run.is_none());
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        scheduler
+            .add_task(Arc::new(TestTask {
+                name: "test_task".to_string(),
+                counter: counter.clone(),
+            }))
+            .await
+            .unwrap();
+        scheduler.add_task(Arc::new(FailingTask)).await.unwrap();
+
+        Scheduler::execute_all_tasks(&scheduler.tasks, &scheduler.metrics_snapshot).await;
+
+        let health = scheduler.health().await;
+        assert_eq!(health.task_count, 2);
+        assert!(health.any_failing);
+        assert!(health.oldest_last_run.is_some());
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_scheduler_run_on_handle() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .unwrap();
+        let driver = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let scheduler = Scheduler::new(Config::default());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        driver.block_on(async {
+            scheduler
+                .add_task(Arc::new(TestTask {
+                    name: "test_task".to_string(),
+                    counter: counter.clone(),
+                }))
+                .await
+                .unwrap();
+            scheduler.run_on(runtime.handle().clone()).await.unwrap();
+        });
+
+        for _ in 0..100 {
+            if counter.load(Ordering::SeqCst) > 0 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(counter.load(Ordering::SeqCst) >= 1);
+
+        driver.block_on(async {
+            assert!(scheduler.health().await.running);
+            scheduler.stop().await.unwrap();
+        });
+    }
+
+    struct DependentTask {
+        name: &'static str,
+        depends_on: Vec<&'static str>,
+        log: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Task for DependentTask {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn depends_on(&self) -> Vec<&str> {
+            self.depends_on.clone()
+        }
+
+        async fn execute(&self) -> Result<()> {
+            let mut log = self.log.lock().unwrap();
+            for dependency in &self.depends_on {
+                if !log.iter().any(|entry| entry == dependency) {
+                    return Err(Error::GenerationError(format!(
+                        "{} ran before {}",
+                        self.name, dependency
+                    )));
+                }
+            }
+            log.push(self.name.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_dependency_order() {
+        let scheduler = Scheduler::new(Config::default());
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        scheduler
+            .add_task(Arc::new(DependentTask {
+                name: "sitemap",
+                depends_on: vec!["pages"],
+                log: log.clone(),
+            }))
+            .await
+            .unwrap();
+        scheduler
+            .add_task(Arc::new(DependentTask {
+                name: "pages",
+                depends_on: vec![],
+                log: log.clone(),
+            }))
+            .await
+            .unwrap();
+
+        Scheduler::execute_all_tasks(&scheduler.tasks, &scheduler.metrics_snapshot).await;
+
+        assert_eq!(*log.lock().unwrap(), vec!["pages", "sitemap"]);
+        assert!(!scheduler.health().await.any_failing);
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_dependency_failure_skips_dependents() {
+        let scheduler = Scheduler::new(Config::default());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        struct AfterFailing(TestTask);
+
+        #[async_trait]
+        impl Task for AfterFailing {
+            fn name(&self) -> &str {
+                self.0.name()
+            }
+
+            fn depends_on(&self) -> Vec<&str> {
+                vec!["failing_task"]
+            }
+
+            async fn execute(&self) -> Result<()> {
+                self.0.execute().await
+            }
+        }
+
+        scheduler.add_task(Arc::new(FailingTask)).await.unwrap();
+        scheduler
+            .add_task(Arc::new(AfterFailing(TestTask {
+                name: "dependent".to_string(),
+                counter: counter.clone(),
+            })))
+            .await
+            .unwrap();
+
+        Scheduler::execute_all_tasks(&scheduler.tasks, &scheduler.metrics_snapshot).await;
+
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+        let metrics = scheduler.metrics().await;
+        assert!(metrics.iter().all(|m| m.failure_count == 1));
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_rejects_dependency_cycle() {
+        let scheduler = Scheduler::new(Config::default());
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        scheduler
+            .add_task(Arc::new(DependentTask {
+                name: "a",
+                depends_on: vec!["b"],
+                log: log.clone(),
+            }))
+            .await
+            .unwrap();
+        let result = scheduler
+            .add_task(Arc::new(DependentTask {
+                name: "b",
+                depends_on: vec!["a"],
+                log: log.clone(),
+            }))
+            .await;
+
+        assert!(matches!(result, Err(Error::SchedulerError(_))));
+        assert_eq!(scheduler.health().await.task_count, 1);
+    }
 }
@@ -0,0 +1,66 @@
+use std::future::Future;
+
+use log::{error, info};
+use tokio::signal;
+
+// Completes on Ctrl-C or, on Unix, SIGTERM.
+pub async fn shutdown_signal() {
+    shutdown_signal_with(std::future::pending()).await
+}
+
+// Like `shutdown_signal`, but also completes when `trigger` does.
+pub async fn shutdown_signal_with<F: Future<Output = ()>>(trigger: F) {
+    let ctrl_c = async {
+        if let Err(e) = signal::ctrl_c().await {
+            error!("Error waiting for Ctrl-C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                error!("Error installing SIGTERM handler: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received Ctrl-C"),
+        _ = terminate => info!("Received SIGTERM"),
+        _ = trigger => info!("Shutdown triggered"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::Notify;
+
+    #[tokio::test]
+    async fn test_shutdown_signal_with_trigger() {
+        let notify = Arc::new(Notify::new());
+
+        let waiter = {
+            let notify = notify.clone();
+            tokio::spawn(async move { shutdown_signal_with(notify.notified()).await })
+        };
+
+        notify.notify_one();
+
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("shutdown future should complete once notified")
+            .unwrap();
+    }
+}
@@ -1,20 +1,48 @@
 use crate::error::{Error, Result};
 use crate::models::{LogEntry, LogLevel, Metric, Trace};
-use chrono::{DateTime, Utc};
-use std::sync::{Arc, RwLock};
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 
 #[derive(Debug, Clone)]
 pub struct MetricStorage {
     metrics: Arc<RwLock<Vec<Metric>>>,
+    sorted_inserts: bool,
+    clamp_counter_resets: bool,
+    max_entries: Option<usize>,
 }
 
 impl MetricStorage {
     pub fn new() -> Self {
         Self {
             metrics: Arc::new(RwLock::new(Vec::new())),
+            sorted_inserts: false,
+            clamp_counter_resets: false,
+            max_entries: None,
         }
     }
 
+    pub fn with_sorted_inserts(mut self, sorted: bool) -> Self {
+        self.sorted_inserts = sorted;
+        self
+    }
+
+    pub fn with_counter_reset_clamping(mut self, clamp: bool) -> Self {
+        self.clamp_counter_resets = clamp;
+        self
+    }
+
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
     pub fn add(&self, metric: Metric) -> Result<()> {
         let mut metrics = self.metrics.write().map_err(|e| {
             Error::Unknown(format!(
@@ -22,10 +50,47 @@ impl MetricStorage {
                 e
             ))
         })?;
-        metrics.push(metric);
+        check_capacity(metrics.len(), self.max_entries)?;
+
+        self.insert(&mut metrics, metric);
+        Ok(())
+    }
+
+    // Pushes a new sample of a counter whose value is the latest matching sample
+    // plus `by`. The read and the push happen under one write lock, so concurrent
+    // increments are never lost.
+    pub fn incr(&self, name: &str, by: f64, labels: HashMap<String, String>) -> Result<()> {
+        let mut metrics = self.metrics.write().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire write lock on metric storage: {}",
+                e
+            ))
+        })?;
+        check_capacity(metrics.len(), self.max_entries)?;
+
+        let current = metrics
+            .iter()
+            .filter(|m| m.name == name && m.labels == labels)
+            .max_by_key(|m| m.timestamp)
+            .map_or(0.0, |m| m.value);
+
+        self.insert(
+            &mut metrics,
+            Metric::new(name, current + by).with_labels(labels),
+        );
         Ok(())
     }
 
+    fn insert(&self, metrics: &mut Vec<Metric>, metric: Metric) {
+        if self.sorted_inserts {
+            // Insert after any equal timestamps so arrival order is kept for ties.
+            let index = metrics.partition_point(|m| m.timestamp <= metric.timestamp);
+            metrics.insert(index, metric);
+        } else {
+            metrics.push(metric);
+        }
+    }
+
     pub fn get_all(&self) -> Result<Vec<Metric>> {
         let metrics = self.metrics.read().map_err(|e| {
             Error::Unknown(format!(
@@ -36,6 +101,19 @@ impl MetricStorage {
         Ok(metrics.clone())
     }
 
+    // The last `n` entries in storage order: the most recently added ones, or
+    // with sorted inserts the newest by timestamp, since late arrivals land
+    // in the middle of the vec.
+    pub fn recent(&self, n: usize) -> Result<Vec<Metric>> {
+        let metrics = self.metrics.read().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire read lock on metric storage: {}",
+                e
+            ))
+        })?;
+        Ok(metrics[metrics.len().saturating_sub(n)..].to_vec())
+    }
+
     pub fn get_by_name(&self, name: &str) -> Result<Vec<Metric>> {
         let metrics = self.metrics.read().map_err(|e| {
             Error::Unknown(format!(
@@ -49,6 +127,38 @@ impl MetricStorage {
         Ok(filtered)
     }
 
+    pub fn rate(&self, name: &str) -> Result<Option<f64>> {
+        let metrics = self.metrics.read().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire read lock on metric storage: {}",
+                e
+            ))
+        })?;
+
+        let mut samples: Vec<&Metric> = metrics.iter().filter(|m| m.name == name).collect();
+        samples.sort_by_key(|m| m.timestamp);
+
+        let (prev, last) = match samples.as_slice() {
+            [.., prev, last] => (prev, last),
+            _ => return Ok(None),
+        };
+
+        let elapsed_secs = (last.timestamp - prev.timestamp)
+            .num_microseconds()
+            .map(|us| us as f64 / 1_000_000.0)
+            .unwrap_or(0.0);
+        if elapsed_secs <= 0.0 {
+            return Ok(None);
+        }
+
+        let delta = last.value - prev.value;
+        if delta < 0.0 && self.clamp_counter_resets {
+            return Ok(Some(0.0));
+        }
+
+        Ok(Some(delta / elapsed_secs))
+    }
+
     pub fn get_by_time_range(
         &self,
         start: DateTime<Utc>,
@@ -61,6 +171,17 @@ impl MetricStorage {
             ))
         })?;
 
+        if self.sorted_inserts {
+            let start_index = metrics.partition_point(|m| m.timestamp < start);
+            let end_index = metrics.partition_point(|m| m.timestamp <= end);
+
+            if start_index >= end_index {
+                return Ok(Vec::new());
+            }
+
+            return Ok(metrics[start_index..end_index].to_vec());
+        }
+
         let filtered = metrics
             .iter()
             .filter(|m| m.timestamp >= start && m.timestamp <= end)
@@ -87,6 +208,46 @@ impl MetricStorage {
         Ok(filtered)
     }
 
+    pub fn names(&self) -> Result<Vec<String>> {
+        let metrics = self.metrics.read().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire read lock on metric storage: {}",
+                e
+            ))
+        })?;
+
+        let names: BTreeSet<&String> = metrics.iter().map(|m| &m.name).collect();
+
+        Ok(names.into_iter().cloned().collect())
+    }
+
+    pub fn label_values(&self, key: &str) -> Result<Vec<String>> {
+        let metrics = self.metrics.read().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire read lock on metric storage: {}",
+                e
+            ))
+        })?;
+
+        let values: BTreeSet<&String> = metrics.iter().filter_map(|m| m.labels.get(key)).collect();
+
+        Ok(values.into_iter().cloned().collect())
+    }
+
+    pub fn prune_older_than(&self, max_age: Duration) -> Result<usize> {
+        let cutoff = Utc::now() - max_age;
+        let mut metrics = self.metrics.write().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire write lock on metric storage: {}",
+                e
+            ))
+        })?;
+
+        let before = metrics.len();
+        metrics.retain(|entry| entry.timestamp >= cutoff);
+        Ok(before - metrics.len())
+    }
+
     pub fn clear(&self) -> Result<()> {
         let mut metrics = self.metrics.write().map_err(|e| {
             Error::Unknown(format!(
@@ -107,6 +268,86 @@ impl MetricStorage {
         })?;
         Ok(metrics.len())
     }
+
+    // Runs `f` under the read lock so callers can inspect metrics without cloning them.
+    pub fn with_all<R, F: FnOnce(&[Metric]) -> R>(&self, f: F) -> Result<R> {
+        let metrics = self.metrics.read().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire read lock on metric storage: {}",
+                e
+            ))
+        })?;
+        Ok(f(&metrics))
+    }
+
+    pub fn find<F: Fn(&Metric) -> bool>(&self, f: F) -> Result<Option<Metric>> {
+        self.with_all(|metrics| metrics.iter().find(|m| f(m)).cloned())
+    }
+
+    pub fn any<F: Fn(&Metric) -> bool>(&self, f: F) -> Result<bool> {
+        self.with_all(|metrics| metrics.iter().any(f))
+    }
+
+    pub fn count_where<F: Fn(&Metric) -> bool>(&self, f: F) -> Result<usize> {
+        self.with_all(|metrics| metrics.iter().filter(|m| f(m)).count())
+    }
+
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<usize> {
+        let metrics = self.metrics.read().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire read lock on metric storage: {}",
+                e
+            ))
+        })?;
+        save_json(path.as_ref(), &metrics)?;
+        Ok(metrics.len())
+    }
+
+    pub fn load_from_file<P: AsRef<Path>>(&self, path: P) -> Result<usize> {
+        let loaded: Vec<Metric> = load_json(path.as_ref())?;
+        let count = loaded.len();
+
+        let mut metrics = self.metrics.write().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire write lock on metric storage: {}",
+                e
+            ))
+        })?;
+        check_batch_capacity(metrics.len(), count, self.max_entries)?;
+        for entry in loaded {
+            self.insert(&mut metrics, entry);
+        }
+        Ok(count)
+    }
+
+    // Appends every entry of `other` under a single write lock. `other` is
+    // snapshotted first, so merging a storage into a clone of itself is safe.
+    pub fn merge_from(&self, other: &MetricStorage) -> Result<()> {
+        self.merge(other, false)
+    }
+
+    // Like `merge_from`, but skips entries that are already stored.
+    pub fn merge_from_dedup(&self, other: &MetricStorage) -> Result<()> {
+        self.merge(other, true)
+    }
+
+    fn merge(&self, other: &MetricStorage, dedup: bool) -> Result<()> {
+        let incoming = other.get_all()?;
+
+        let mut metrics = self.metrics.write().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire write lock on metric storage: {}",
+                e
+            ))
+        })?;
+        let pending = dedup_incoming(&metrics, incoming, dedup)?;
+
+        check_batch_capacity(metrics.len(), pending.len(), self.max_entries)?;
+        for entry in pending {
+            self.insert(&mut metrics, entry);
+        }
+        Ok(())
+    }
 }
 
 impl Default for MetricStorage {
@@ -115,18 +356,32 @@ impl Default for MetricStorage {
     }
 }
 
+// Longest duration first; ties are broken by name so the order is stable.
+pub(crate) fn slowest_first(a: &Trace, b: &Trace) -> Ordering {
+    b.duration_ms
+        .cmp(&a.duration_ms)
+        .then_with(|| a.name.cmp(&b.name))
+}
+
 #[derive(Debug, Clone)]
 pub struct TraceStorage {
     traces: Arc<RwLock<Vec<Trace>>>,
+    max_entries: Option<usize>,
 }
 
 impl TraceStorage {
     pub fn new() -> Self {
         Self {
             traces: Arc::new(RwLock::new(Vec::new())),
+            max_entries: None,
         }
     }
 
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
     pub fn add(&self, trace: Trace) -> Result<()> {
         let mut traces = self.traces.write().map_err(|e| {
             Error::Unknown(format!(
@@ -134,6 +389,7 @@ impl TraceStorage {
                 e
             ))
         })?;
+        check_capacity(traces.len(), self.max_entries)?;
         traces.push(trace);
         Ok(())
     }
@@ -148,6 +404,16 @@ impl TraceStorage {
         Ok(traces.clone())
     }
 
+    pub fn recent(&self, n: usize) -> Result<Vec<Trace>> {
+        let traces = self.traces.read().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire read lock on trace storage: {}",
+                e
+            ))
+        })?;
+        Ok(traces[traces.len().saturating_sub(n)..].to_vec())
+    }
+
     pub fn get_by_id(&self, span_id: &str) -> Result<Option<Trace>> {
         let traces = self.traces.read().map_err(|e| {
             Error::Unknown(format!(
@@ -183,13 +449,78 @@ impl TraceStorage {
 
         let filtered = traces
             .iter()
-            .filter(|t| t.parent_id.as_ref().map_or(false, |id| id == parent_id))
+            .filter(|t| t.parent_id.as_deref() == Some(parent_id))
             .cloned()
             .collect();
 
         Ok(filtered)
     }
 
+    pub fn names(&self) -> Result<Vec<String>> {
+        let traces = self.traces.read().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire read lock on trace storage: {}",
+                e
+            ))
+        })?;
+
+        let names: BTreeSet<&String> = traces.iter().map(|t| &t.name).collect();
+
+        Ok(names.into_iter().cloned().collect())
+    }
+
+    pub fn slowest(&self, n: usize) -> Result<Vec<Trace>> {
+        let traces = self.traces.read().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire read lock on trace storage: {}",
+                e
+            ))
+        })?;
+
+        let mut sorted: Vec<&Trace> = traces.iter().collect();
+        sorted.sort_by(|a, b| slowest_first(a, b));
+
+        Ok(sorted.into_iter().take(n).cloned().collect())
+    }
+
+    // Counts spans named `name` per `<= bucket_ms` bucket, in ascending bucket
+    // order, followed by a `u64::MAX` bucket standing in for +Inf. Non-cumulative
+    // counts place each span in the first bucket it fits.
+    pub fn histogram(
+        &self,
+        name: &str,
+        buckets: &[u64],
+        cumulative: bool,
+    ) -> Result<Vec<(u64, usize)>> {
+        let traces = self.traces.read().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire read lock on trace storage: {}",
+                e
+            ))
+        })?;
+
+        let mut bounds = buckets.to_vec();
+        bounds.sort_unstable();
+        bounds.dedup();
+        if bounds.last() != Some(&u64::MAX) {
+            bounds.push(u64::MAX);
+        }
+
+        let mut counts = vec![0; bounds.len()];
+        for trace in traces.iter().filter(|t| t.name == name) {
+            let index = bounds.partition_point(|&bound| bound < trace.duration_ms);
+            counts[index] += 1;
+        }
+
+        if cumulative {
+            for i in 1..counts.len() {
+                counts[i] += counts[i - 1];
+            }
+        }
+
+        Ok(bounds.into_iter().zip(counts).collect())
+    }
+
     pub fn get_roots(&self) -> Result<Vec<Trace>> {
         let traces = self.traces.read().map_err(|e| {
             Error::Unknown(format!(
@@ -224,6 +555,21 @@ impl TraceStorage {
         Ok(filtered)
     }
 
+    // Traces age by `end_time`, so a long span isn't dropped while still recent.
+    pub fn prune_older_than(&self, max_age: Duration) -> Result<usize> {
+        let cutoff = Utc::now() - max_age;
+        let mut traces = self.traces.write().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire write lock on trace storage: {}",
+                e
+            ))
+        })?;
+
+        let before = traces.len();
+        traces.retain(|entry| entry.end_time >= cutoff);
+        Ok(before - traces.len())
+    }
+
     pub fn clear(&self) -> Result<()> {
         let mut traces = self.traces.write().map_err(|e| {
             Error::Unknown(format!(
@@ -244,6 +590,60 @@ impl TraceStorage {
         })?;
         Ok(traces.len())
     }
+
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<usize> {
+        let traces = self.traces.read().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire read lock on trace storage: {}",
+                e
+            ))
+        })?;
+        save_json(path.as_ref(), &traces)?;
+        Ok(traces.len())
+    }
+
+    pub fn load_from_file<P: AsRef<Path>>(&self, path: P) -> Result<usize> {
+        let loaded: Vec<Trace> = load_json(path.as_ref())?;
+        let count = loaded.len();
+
+        let mut traces = self.traces.write().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire write lock on trace storage: {}",
+                e
+            ))
+        })?;
+        check_batch_capacity(traces.len(), count, self.max_entries)?;
+        for entry in loaded {
+            traces.push(entry);
+        }
+        Ok(count)
+    }
+
+    pub fn merge_from(&self, other: &TraceStorage) -> Result<()> {
+        self.merge(other, false)
+    }
+
+    pub fn merge_from_dedup(&self, other: &TraceStorage) -> Result<()> {
+        self.merge(other, true)
+    }
+
+    fn merge(&self, other: &TraceStorage, dedup: bool) -> Result<()> {
+        let incoming = other.get_all()?;
+
+        let mut traces = self.traces.write().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire write lock on trace storage: {}",
+                e
+            ))
+        })?;
+        let pending = dedup_incoming(&traces, incoming, dedup)?;
+
+        check_batch_capacity(traces.len(), pending.len(), self.max_entries)?;
+        for entry in pending {
+            traces.push(entry);
+        }
+        Ok(())
+    }
 }
 
 impl Default for TraceStorage {
@@ -252,18 +652,85 @@ impl Default for TraceStorage {
     }
 }
 
+// An NDJSON file that every added log is appended to. If the file is moved or
+// deleted (e.g. by logrotate) it is reopened at the original path.
+#[derive(Debug)]
+struct AppendFile {
+    path: PathBuf,
+    file: File,
+}
+
+impl AppendFile {
+    fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+        })
+    }
+
+    fn is_rotated(&self) -> bool {
+        let on_disk = match std::fs::metadata(&self.path) {
+            Ok(metadata) => metadata,
+            Err(_) => return true,
+        };
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            match self.file.metadata() {
+                Ok(open) => open.ino() != on_disk.ino() || open.dev() != on_disk.dev(),
+                Err(_) => true,
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = on_disk;
+            false
+        }
+    }
+
+    fn append(&mut self, line: &str) -> Result<()> {
+        if self.is_rotated() {
+            *self = Self::open(&self.path)?;
+        }
+
+        writeln!(self.file, "{}", line)?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LogStorage {
     logs: Arc<RwLock<Vec<LogEntry>>>,
+    max_entries: Option<usize>,
+    append_file: Option<Arc<Mutex<AppendFile>>>,
 }
 
 impl LogStorage {
     pub fn new() -> Self {
         Self {
             logs: Arc::new(RwLock::new(Vec::new())),
+            max_entries: None,
+            append_file: None,
         }
     }
 
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    // Every successful add is also written as a JSON line to `path`.
+    pub fn with_append_file<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
+        let append_file = AppendFile::open(path.as_ref())?;
+        self.append_file = Some(Arc::new(Mutex::new(append_file)));
+        Ok(self)
+    }
+
     pub fn add(&self, log: LogEntry) -> Result<()> {
         let mut logs = self.logs.write().map_err(|e| {
             Error::Unknown(format!(
@@ -271,6 +738,17 @@ impl LogStorage {
                 e
             ))
         })?;
+        check_capacity(logs.len(), self.max_entries)?;
+
+        // Written before the push so a failed append doesn't leave the log
+        // in memory only.
+        if let Some(append_file) = &self.append_file {
+            let mut append_file = append_file.lock().map_err(|e| {
+                Error::Unknown(format!("Failed to acquire lock on log append file: {}", e))
+            })?;
+            append_file.append(&log.to_json_line()?)?;
+        }
+
         logs.push(log);
         Ok(())
     }
@@ -282,6 +760,13 @@ impl LogStorage {
         Ok(logs.clone())
     }
 
+    pub fn recent(&self, n: usize) -> Result<Vec<LogEntry>> {
+        let logs = self.logs.read().map_err(|e| {
+            Error::Unknown(format!("Failed to acquire read lock on log storage: {}", e))
+        })?;
+        Ok(logs[logs.len().saturating_sub(n)..].to_vec())
+    }
+
     pub fn get_by_level(&self, min_level: LogLevel) -> Result<Vec<LogEntry>> {
         let logs = self.logs.read().map_err(|e| {
             Error::Unknown(format!("Failed to acquire read lock on log storage: {}", e))
@@ -310,39 +795,89 @@ impl LogStorage {
         Ok(filtered)
     }
 
-    pub fn get_by_message_contains(&self, substring: &str) -> Result<Vec<LogEntry>> {
+    // An empty `sources` slice matches nothing rather than everything.
+    pub fn get_by_sources(&self, sources: &[&str]) -> Result<Vec<LogEntry>> {
         let logs = self.logs.read().map_err(|e| {
             Error::Unknown(format!("Failed to acquire read lock on log storage: {}", e))
         })?;
 
         let filtered = logs
             .iter()
-            .filter(|l| l.message.contains(substring))
+            .filter(|l| sources.contains(&l.source.as_str()))
             .cloned()
             .collect();
 
         Ok(filtered)
     }
 
-    pub fn get_by_time_range(
-        &self,
-        start: DateTime<Utc>,
-        end: DateTime<Utc>,
-    ) -> Result<Vec<LogEntry>> {
+    pub fn get_by_message_contains(&self, substring: &str) -> Result<Vec<LogEntry>> {
         let logs = self.logs.read().map_err(|e| {
             Error::Unknown(format!("Failed to acquire read lock on log storage: {}", e))
         })?;
 
         let filtered = logs
             .iter()
-            .filter(|l| l.timestamp >= start && l.timestamp <= end)
+            .filter(|l| l.message.contains(substring))
             .cloned()
             .collect();
 
         Ok(filtered)
     }
 
-    pub fn clear(&self) -> Result<()> {
+    pub fn get_by_time_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<LogEntry>> {
+        let logs = self.logs.read().map_err(|e| {
+            Error::Unknown(format!("Failed to acquire read lock on log storage: {}", e))
+        })?;
+
+        let filtered = logs
+            .iter()
+            .filter(|l| l.timestamp >= start && l.timestamp <= end)
+            .cloned()
+            .collect();
+
+        Ok(filtered)
+    }
+
+    pub fn count_by_level(
+        &self,
+        range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    ) -> Result<HashMap<LogLevel, usize>> {
+        let logs = self.logs.read().map_err(|e| {
+            Error::Unknown(format!("Failed to acquire read lock on log storage: {}", e))
+        })?;
+
+        let mut counts = HashMap::new();
+        for log in logs.iter() {
+            if let Some((start, end)) = range {
+                if log.timestamp < start || log.timestamp > end {
+                    continue;
+                }
+            }
+            *counts.entry(log.level).or_insert(0) += 1;
+        }
+
+        Ok(counts)
+    }
+
+    pub fn prune_older_than(&self, max_age: Duration) -> Result<usize> {
+        let cutoff = Utc::now() - max_age;
+        let mut logs = self.logs.write().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire write lock on log storage: {}",
+                e
+            ))
+        })?;
+
+        let before = logs.len();
+        logs.retain(|entry| entry.timestamp >= cutoff);
+        Ok(before - logs.len())
+    }
+
+    pub fn clear(&self) -> Result<()> {
         let mut logs = self.logs.write().map_err(|e| {
             Error::Unknown(format!(
                 "Failed to acquire write lock on log storage: {}",
@@ -359,6 +894,67 @@ impl LogStorage {
         })?;
         Ok(logs.len())
     }
+
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<usize> {
+        let logs = self.logs.read().map_err(|e| {
+            Error::Unknown(format!("Failed to acquire read lock on log storage: {}", e))
+        })?;
+        save_json(path.as_ref(), &logs)?;
+        Ok(logs.len())
+    }
+
+    // Loaded entries already live on disk, so they skip the append file.
+    pub fn load_from_file<P: AsRef<Path>>(&self, path: P) -> Result<usize> {
+        let loaded: Vec<LogEntry> = load_json(path.as_ref())?;
+        let count = loaded.len();
+
+        let mut logs = self.logs.write().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire write lock on log storage: {}",
+                e
+            ))
+        })?;
+        check_batch_capacity(logs.len(), count, self.max_entries)?;
+        for entry in loaded {
+            logs.push(entry);
+        }
+        Ok(count)
+    }
+
+    // Merged entries are written to the append file like added ones.
+    pub fn merge_from(&self, other: &LogStorage) -> Result<()> {
+        self.merge(other, false)
+    }
+
+    pub fn merge_from_dedup(&self, other: &LogStorage) -> Result<()> {
+        self.merge(other, true)
+    }
+
+    fn merge(&self, other: &LogStorage, dedup: bool) -> Result<()> {
+        let incoming = other.get_all()?;
+
+        let mut logs = self.logs.write().map_err(|e| {
+            Error::Unknown(format!(
+                "Failed to acquire write lock on log storage: {}",
+                e
+            ))
+        })?;
+        let pending = dedup_incoming(&logs, incoming, dedup)?;
+
+        check_batch_capacity(logs.len(), pending.len(), self.max_entries)?;
+        if let Some(append_file) = &self.append_file {
+            let mut append_file = append_file.lock().map_err(|e| {
+                Error::Unknown(format!("Failed to acquire lock on log append file: {}", e))
+            })?;
+            for entry in &pending {
+                append_file.append(&entry.to_json_line()?)?;
+            }
+        }
+        for entry in pending {
+            logs.push(entry);
+        }
+        Ok(())
+    }
 }
 
 impl Default for LogStorage {
@@ -367,10 +963,62 @@ impl Default for LogStorage {
     }
 }
 
+// Backs every storage's `save_to_file`. Writing goes through a temporary file,
+// so a crash mid-save never leaves a truncated file behind.
+fn save_json<T: Serialize>(path: &Path, entries: &[T]) -> Result<()> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+
+    std::fs::write(&tmp_path, serde_json::to_vec(entries)?)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+fn load_json<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    let content = std::fs::read(path)?;
+    Ok(serde_json::from_slice(&content)?)
+}
+
+// The models don't implement `Hash`, so dedup keys on their JSON form, where
+// maps serialize with sorted keys.
+fn dedup_incoming<T: Serialize>(existing: &[T], incoming: Vec<T>, dedup: bool) -> Result<Vec<T>> {
+    if !dedup {
+        return Ok(incoming);
+    }
+
+    let dedup_key = |entry: &T| -> Result<String> { Ok(serde_json::to_value(entry)?.to_string()) };
+    let mut seen = existing
+        .iter()
+        .map(dedup_key)
+        .collect::<Result<HashSet<String>>>()?;
+
+    let mut pending = Vec::with_capacity(incoming.len());
+    for entry in incoming {
+        if seen.insert(dedup_key(&entry)?) {
+            pending.push(entry);
+        }
+    }
+    Ok(pending)
+}
+
+fn check_capacity(len: usize, max_entries: Option<usize>) -> Result<()> {
+    check_batch_capacity(len, 1, max_entries)
+}
+
+// Batches are checked up front so a batch that doesn't fit leaves the storage
+// unchanged instead of half-filled.
+fn check_batch_capacity(len: usize, additional: usize, max_entries: Option<usize>) -> Result<()> {
+    match max_entries {
+        Some(max) if len + additional > max => {
+            Err(Error::GenerationError("storage full".to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Duration;
 
     #[test]
     fn test_metric_storage_add_and_get() {
@@ -426,6 +1074,187 @@ mod tests {
         assert!(server2_metrics[0].has_label_value("host", "server-2"));
     }
 
+    #[test]
+    fn test_metric_storage_find() {
+        let storage = MetricStorage::new();
+
+        storage
+            .add(Metric::new("cpu_usage", 75.5).with_label("host", "server-1"))
+            .unwrap();
+        storage
+            .add(Metric::new("cpu_usage", 80.2).with_label("host", "server-2"))
+            .unwrap();
+        storage
+            .add(Metric::new("memory_usage", 42.8).with_label("host", "server-2"))
+            .unwrap();
+
+        let found = storage
+            .find(|m| m.has_label_value("host", "server-2"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.value, 80.2);
+        assert!(storage
+            .find(|m| m.has_label_value("host", "server-3"))
+            .unwrap()
+            .is_none());
+
+        assert!(storage.any(|m| m.name == "memory_usage").unwrap());
+        assert!(!storage.any(|m| m.value > 100.0).unwrap());
+        assert_eq!(storage.count_where(|m| m.name == "cpu_usage").unwrap(), 2);
+        assert_eq!(storage.with_all(|metrics| metrics.len()).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_metric_storage_label_values() {
+        let storage = MetricStorage::new();
+
+        storage
+            .add(Metric::new("cpu_usage", 75.5).with_label("host", "b"))
+            .unwrap();
+        storage
+            .add(Metric::new("cpu_usage", 80.2).with_label("host", "a"))
+            .unwrap();
+        storage
+            .add(Metric::new("memory_usage", 42.8).with_label("host", "a"))
+            .unwrap();
+        storage.add(Metric::new("uptime", 12.0)).unwrap();
+
+        assert_eq!(storage.label_values("host").unwrap(), vec!["a", "b"]);
+        assert!(storage.label_values("region").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_metric_storage_names() {
+        let storage = MetricStorage::new();
+        assert!(storage.names().unwrap().is_empty());
+
+        storage.add(Metric::new("memory_usage", 42.8)).unwrap();
+        storage.add(Metric::new("cpu_usage", 75.5)).unwrap();
+        storage.add(Metric::new("memory_usage", 43.1)).unwrap();
+
+        assert_eq!(storage.names().unwrap(), vec!["cpu_usage", "memory_usage"]);
+    }
+
+    #[test]
+    fn test_metric_storage_rate() {
+        let now = Utc::now();
+        let storage = MetricStorage::new();
+
+        assert_eq!(storage.rate("requests").unwrap(), None);
+
+        storage
+            .add(Metric::with_timestamp(
+                "requests",
+                100.0,
+                now - Duration::seconds(20),
+            ))
+            .unwrap();
+        assert_eq!(storage.rate("requests").unwrap(), None);
+
+        storage
+            .add(Metric::with_timestamp("requests", 250.0, now))
+            .unwrap();
+        storage
+            .add(Metric::with_timestamp(
+                "requests",
+                200.0,
+                now - Duration::seconds(10),
+            ))
+            .unwrap();
+        storage
+            .add(Metric::with_timestamp("errors", 5.0, now))
+            .unwrap();
+
+        assert_eq!(storage.rate("requests").unwrap(), Some(5.0));
+
+        storage
+            .add(Metric::with_timestamp("errors", 5.0, now))
+            .unwrap();
+        assert_eq!(storage.rate("errors").unwrap(), None);
+    }
+
+    #[test]
+    fn test_metric_storage_rate_counter_reset() {
+        let now = Utc::now();
+        let samples = [
+            Metric::with_timestamp("requests", 500.0, now - Duration::seconds(10)),
+            Metric::with_timestamp("requests", 20.0, now),
+        ];
+
+        let raw = MetricStorage::new();
+        let clamped = MetricStorage::new().with_counter_reset_clamping(true);
+        for metric in samples {
+            raw.add(metric.clone()).unwrap();
+            clamped.add(metric).unwrap();
+        }
+
+        assert_eq!(raw.rate("requests").unwrap(), Some(-48.0));
+        assert_eq!(clamped.rate("requests").unwrap(), Some(0.0));
+    }
+
+    #[test]
+    fn test_metric_storage_sorted_inserts() {
+        let storage = MetricStorage::new().with_sorted_inserts(true);
+        let now = Utc::now();
+
+        for offset in [3, 1, 4, 0, 2] {
+            storage
+                .add(Metric::with_timestamp(
+                    &format!("m{}", offset),
+                    offset as f64,
+                    now + Duration::minutes(offset),
+                ))
+                .unwrap();
+        }
+
+        let all_metrics = storage.get_all().unwrap();
+        assert_eq!(all_metrics.len(), 5);
+        assert!(all_metrics
+            .windows(2)
+            .all(|pair| pair[0].timestamp <= pair[1].timestamp));
+
+        let subset = storage
+            .get_by_time_range(now + Duration::minutes(1), now + Duration::minutes(3))
+            .unwrap();
+        let names: Vec<&str> = subset.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["m1", "m2", "m3"]);
+    }
+
+    #[test]
+    fn test_metric_storage_sorted_range_matches_scan() {
+        let sorted = MetricStorage::new().with_sorted_inserts(true);
+        let unsorted = MetricStorage::new();
+        let now = Utc::now();
+
+        for offset in [7, 2, 9, 2, 0, 5, 3, 8, 1, 6] {
+            let metric = Metric::with_timestamp(
+                &format!("m{}", offset),
+                offset as f64,
+                now + Duration::seconds(offset),
+            );
+            sorted.add(metric.clone()).unwrap();
+            unsorted.add(metric).unwrap();
+        }
+
+        let ranges = [
+            (now, now + Duration::seconds(9)),
+            (now + Duration::seconds(2), now + Duration::seconds(5)),
+            (now + Duration::seconds(4), now + Duration::seconds(4)),
+            (now + Duration::seconds(20), now + Duration::seconds(30)),
+            (now + Duration::seconds(5), now + Duration::seconds(2)),
+        ];
+
+        for (start, end) in ranges {
+            let mut from_search = sorted.get_by_time_range(start, end).unwrap();
+            let mut from_scan = unsorted.get_by_time_range(start, end).unwrap();
+
+            from_search.sort_by_key(|m| m.timestamp);
+            from_scan.sort_by_key(|m| m.timestamp);
+
+            assert_eq!(from_search, from_scan);
+        }
+    }
+
     #[test]
     fn test_trace_storage_add_and_get() {
         let storage = TraceStorage::new();
@@ -477,6 +1306,86 @@ mod tests {
         assert!(children.contains(&child2));
     }
 
+    #[test]
+    fn test_trace_storage_names() {
+        let storage = TraceStorage::new();
+
+        storage.add(Trace::new("db query", 45)).unwrap();
+        storage.add(Trace::new("API Request", 157)).unwrap();
+        storage.add(Trace::new("db query", 30)).unwrap();
+
+        assert_eq!(storage.names().unwrap(), vec!["API Request", "db query"]);
+    }
+
+    #[test]
+    fn test_trace_storage_slowest() {
+        let storage = TraceStorage::new();
+
+        storage.add(Trace::new("render", 40)).unwrap();
+        storage.add(Trace::new("db_query", 250)).unwrap();
+        storage.add(Trace::new("cache_lookup", 5)).unwrap();
+        storage.add(Trace::new("auth", 120)).unwrap();
+        storage.add(Trace::new("api_call", 120)).unwrap();
+
+        let names: Vec<String> = storage
+            .slowest(3)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["db_query", "api_call", "auth"]);
+
+        assert_eq!(storage.slowest(10).unwrap().len(), 5);
+        assert!(storage.slowest(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_storage_recent() {
+        let log_storage = LogStorage::new();
+        for i in 0..20 {
+            log_storage
+                .add(LogEntry::new(&format!("log {}", i), LogLevel::Info, "app"))
+                .unwrap();
+        }
+
+        let messages: Vec<String> = log_storage
+            .recent(5)
+            .unwrap()
+            .into_iter()
+            .map(|log| log.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec!["log 15", "log 16", "log 17", "log 18", "log 19"]
+        );
+        assert_eq!(log_storage.recent(50).unwrap().len(), 20);
+        assert!(log_storage.recent(0).unwrap().is_empty());
+
+        let metric_storage = MetricStorage::new();
+        metric_storage.add(Metric::new("a", 1.0)).unwrap();
+        metric_storage.add(Metric::new("b", 2.0)).unwrap();
+        assert_eq!(metric_storage.recent(1).unwrap()[0].name, "b");
+
+        let sorted_storage = MetricStorage::new().with_sorted_inserts(true);
+        let now = Utc::now();
+        sorted_storage
+            .add(Metric::with_timestamp("newest", 1.0, now))
+            .unwrap();
+        sorted_storage
+            .add(Metric::with_timestamp(
+                "late",
+                2.0,
+                now - Duration::minutes(5),
+            ))
+            .unwrap();
+        assert_eq!(sorted_storage.recent(1).unwrap()[0].name, "newest");
+
+        let trace_storage = TraceStorage::new();
+        assert!(trace_storage.recent(3).unwrap().is_empty());
+        trace_storage.add(Trace::new("a", 1)).unwrap();
+        assert_eq!(trace_storage.recent(3).unwrap().len(), 1);
+    }
+
     #[test]
     fn test_log_storage_add_and_get() {
         let storage = LogStorage::new();
@@ -538,6 +1447,27 @@ mod tests {
         assert!(app_logs.iter().all(|l| l.source == "app"));
     }
 
+    #[test]
+    fn test_log_storage_get_by_sources() {
+        let storage = LogStorage::new();
+
+        for (message, source) in [
+            ("App started", "app"),
+            ("DB connected", "database"),
+            ("User logged in", "auth"),
+            ("Request received", "app"),
+        ] {
+            storage
+                .add(LogEntry::new(message, LogLevel::Info, source))
+                .unwrap();
+        }
+
+        let logs = storage.get_by_sources(&["app", "auth"]).unwrap();
+        assert_eq!(logs.len(), 3);
+        assert!(logs.iter().all(|l| l.source != "database"));
+        assert!(storage.get_by_sources(&[]).unwrap().is_empty());
+    }
+
     #[test]
     fn test_log_storage_get_by_message_contains() {
         let storage = LogStorage::new();
@@ -569,6 +1499,67 @@ mod tests {
         assert!(login_logs.iter().all(|l| l.message.contains("login")));
     }
 
+    #[test]
+    fn test_log_storage_count_by_level() {
+        let now = Utc::now();
+        let two_hours_ago = now - Duration::hours(2);
+        let storage = LogStorage::new();
+
+        storage
+            .add(LogEntry::with_timestamp(
+                "Info 1",
+                LogLevel::Info,
+                "app",
+                now,
+            ))
+            .unwrap();
+        storage
+            .add(LogEntry::with_timestamp(
+                "Info 2",
+                LogLevel::Info,
+                "app",
+                now,
+            ))
+            .unwrap();
+        storage
+            .add(LogEntry::with_timestamp(
+                "Error 1",
+                LogLevel::Error,
+                "app",
+                now,
+            ))
+            .unwrap();
+        storage
+            .add(LogEntry::with_timestamp(
+                "Old error",
+                LogLevel::Error,
+                "app",
+                two_hours_ago,
+            ))
+            .unwrap();
+        storage
+            .add(LogEntry::with_timestamp(
+                "Old debug",
+                LogLevel::Debug,
+                "app",
+                two_hours_ago,
+            ))
+            .unwrap();
+
+        let counts = storage.count_by_level(None).unwrap();
+        assert_eq!(counts.get(&LogLevel::Info), Some(&2));
+        assert_eq!(counts.get(&LogLevel::Error), Some(&2));
+        assert_eq!(counts.get(&LogLevel::Debug), Some(&1));
+        assert_eq!(counts.get(&LogLevel::Warning), None);
+
+        let recent = storage
+            .count_by_level(Some((now - Duration::hours(1), now)))
+            .unwrap();
+        assert_eq!(recent.get(&LogLevel::Info), Some(&2));
+        assert_eq!(recent.get(&LogLevel::Error), Some(&1));
+        assert_eq!(recent.get(&LogLevel::Debug), None);
+    }
+
     #[test]
     fn test_storage_clear() {
         let metric_storage = MetricStorage::new();
@@ -651,4 +1642,222 @@ mod tests {
         assert_eq!(recent_logs.len(), 1);
         assert_eq!(recent_logs[0].message, "Recent log");
     }
+
+    #[test]
+    fn test_storage_max_entries() {
+        let metric_storage = MetricStorage::new().with_max_entries(2);
+        metric_storage.add(Metric::new("cpu", 1.0)).unwrap();
+        metric_storage.add(Metric::new("cpu", 2.0)).unwrap();
+        let result = metric_storage.add(Metric::new("cpu", 3.0));
+        assert!(matches!(result, Err(Error::GenerationError(msg)) if msg == "storage full"));
+        assert_eq!(metric_storage.count().unwrap(), 2);
+
+        let trace_storage = TraceStorage::new().with_max_entries(1);
+        trace_storage.add(Trace::new("a", 1)).unwrap();
+        assert!(trace_storage.add(Trace::new("b", 1)).is_err());
+        assert_eq!(trace_storage.count().unwrap(), 1);
+
+        let log_storage = LogStorage::new().with_max_entries(1);
+        log_storage
+            .add(LogEntry::new("one", LogLevel::Info, "app"))
+            .unwrap();
+        assert!(log_storage
+            .add(LogEntry::new("two", LogLevel::Info, "app"))
+            .is_err());
+        assert_eq!(log_storage.count().unwrap(), 1);
+
+        log_storage.clear().unwrap();
+        assert!(log_storage
+            .add(LogEntry::new("three", LogLevel::Info, "app"))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_storage_max_entries_rejects_whole_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.json");
+        let saved = MetricStorage::new();
+        for value in [1.0, 2.0, 3.0] {
+            saved.add(Metric::new("cpu", value)).unwrap();
+        }
+        saved.save_to_file(&path).unwrap();
+
+        let metric_storage = MetricStorage::new().with_max_entries(4);
+        metric_storage.add(Metric::new("cpu", 0.0)).unwrap();
+        metric_storage.add(Metric::new("cpu", 0.5)).unwrap();
+        assert!(metric_storage.load_from_file(&path).is_err());
+        assert_eq!(metric_storage.count().unwrap(), 2);
+        assert!(metric_storage.merge_from(&saved).is_err());
+        assert_eq!(metric_storage.count().unwrap(), 2);
+
+        let trace_storage = TraceStorage::new().with_max_entries(1);
+        let other = TraceStorage::new();
+        other.add(Trace::new("a", 1)).unwrap();
+        other.add(Trace::new("b", 1)).unwrap();
+        assert!(trace_storage.merge_from(&other).is_err());
+        assert_eq!(trace_storage.count().unwrap(), 0);
+
+        let log_storage = LogStorage::new().with_max_entries(1);
+        let other = LogStorage::new();
+        let log = LogEntry::new("one", LogLevel::Info, "app");
+        other.add(log.clone()).unwrap();
+        other.add(log).unwrap();
+        assert!(log_storage.merge_from(&other).is_err());
+        assert!(log_storage.merge_from_dedup(&other).is_ok());
+        assert_eq!(log_storage.count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_metric_storage_incr() {
+        let storage = MetricStorage::new();
+        let labels: HashMap<String, String> = [("route".to_string(), "/".to_string())]
+            .into_iter()
+            .collect();
+
+        for _ in 0..3 {
+            storage.incr("hits", 1.0, labels.clone()).unwrap();
+        }
+        storage.incr("hits", 5.0, HashMap::new()).unwrap();
+
+        let hits = storage.get_by_name("hits").unwrap();
+        assert_eq!(hits.len(), 4);
+
+        let latest = hits
+            .iter()
+            .filter(|m| m.labels == labels)
+            .max_by_key(|m| m.timestamp)
+            .unwrap();
+        assert_eq!(latest.value, 3.0);
+        assert_eq!(
+            hits.iter().find(|m| m.labels.is_empty()).unwrap().value,
+            5.0
+        );
+    }
+
+    #[test]
+    fn test_log_storage_append_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs.ndjson");
+        let storage = LogStorage::new().with_append_file(&path).unwrap();
+
+        storage
+            .add(LogEntry::new("Server started", LogLevel::Info, "app"))
+            .unwrap();
+        storage
+            .add(LogEntry::new("Cache miss", LogLevel::Warning, "cache"))
+            .unwrap();
+
+        // Simulate rotation: the current file is moved away and a fresh one
+        // should be created for the next write.
+        let rotated = dir.path().join("logs.ndjson.1");
+        std::fs::rename(&path, &rotated).unwrap();
+        storage
+            .add(LogEntry::new("Disk full", LogLevel::Error, "app"))
+            .unwrap();
+
+        let mut lines: Vec<String> = std::fs::read_to_string(&rotated)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        lines.extend(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .lines()
+                .map(str::to_string),
+        );
+        assert_eq!(lines.len(), 3);
+
+        let entries: Vec<LogEntry> = lines
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries, storage.get_all().unwrap());
+    }
+
+    #[test]
+    fn test_metric_storage_prune_older_than() {
+        let storage = MetricStorage::new();
+        storage
+            .add(Metric::with_timestamp(
+                "cpu",
+                50.0,
+                Utc::now() - Duration::hours(25),
+            ))
+            .unwrap();
+        storage.add(Metric::new("cpu", 80.0)).unwrap();
+
+        assert_eq!(storage.prune_older_than(Duration::hours(24)).unwrap(), 1);
+
+        let remaining = storage.get_all().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].value, 80.0);
+        assert_eq!(storage.prune_older_than(Duration::hours(24)).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_storage_merge_from() {
+        let shared = Metric::new("cpu", 50.0);
+
+        let live = MetricStorage::new();
+        live.add(shared.clone()).unwrap();
+        live.add(Metric::new("cpu", 60.0)).unwrap();
+        live.add(Metric::new("memory", 4.2)).unwrap();
+
+        let loaded = MetricStorage::new();
+        loaded.add(shared).unwrap();
+        loaded.add(Metric::new("disk", 75.0)).unwrap();
+
+        let merged = MetricStorage::new();
+        merged.merge_from(&live).unwrap();
+        merged.merge_from(&loaded).unwrap();
+        assert_eq!(merged.count().unwrap(), 5);
+
+        live.merge_from_dedup(&loaded).unwrap();
+        assert_eq!(live.count().unwrap(), 4);
+
+        let traces = TraceStorage::new();
+        traces.add(Trace::new("API Request", 157)).unwrap();
+        traces.merge_from(&traces.clone()).unwrap();
+        assert_eq!(traces.count().unwrap(), 2);
+
+        let logs = LogStorage::new();
+        let other_logs = LogStorage::new();
+        other_logs
+            .add(LogEntry::new("Server started", LogLevel::Info, "app"))
+            .unwrap();
+        logs.merge_from_dedup(&other_logs).unwrap();
+        logs.merge_from_dedup(&other_logs).unwrap();
+        assert_eq!(logs.count().unwrap(), 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let append_path = dir.path().join("logs.ndjson");
+        let durable = LogStorage::new().with_append_file(&append_path).unwrap();
+        durable.merge_from(&other_logs).unwrap();
+        let lines = std::fs::read_to_string(&append_path).unwrap();
+        assert_eq!(lines.lines().count(), 1);
+        assert!(lines.contains("Server started"));
+    }
+
+    #[test]
+    fn test_trace_storage_histogram() {
+        let storage = TraceStorage::new();
+        for duration_ms in [10, 50, 200] {
+            storage.add(Trace::new("API Request", duration_ms)).unwrap();
+        }
+        storage.add(Trace::new("Database Query", 5)).unwrap();
+
+        assert_eq!(
+            storage.histogram("API Request", &[50, 100], false).unwrap(),
+            vec![(50, 2), (100, 0), (u64::MAX, 1)]
+        );
+        assert_eq!(
+            storage.histogram("API Request", &[100, 50], true).unwrap(),
+            vec![(50, 2), (100, 2), (u64::MAX, 3)]
+        );
+        assert_eq!(
+            storage.histogram("Missing", &[50], true).unwrap(),
+            vec![(50, 0), (u64::MAX, 0)]
+        );
+    }
 }
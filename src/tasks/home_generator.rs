@@ -1,13 +1,17 @@
-use crate::error::Result;
-use crate::models::{LogEntry, Metric, Trace};
+use crate::error::{Error, Result};
+use crate::models::{LogEntry, LogLevel, Metric, Trace};
 use crate::scheduler::Task;
-use crate::storage::{LogStorage, MetricStorage, TraceStorage};
-use crate::templating::{HtmlRenderer, TemplateContext, TemplateEngine, TextRenderer};
+use crate::storage::{slowest_first, LogStorage, MetricStorage, TraceStorage};
+use crate::tasks::manifest::BuildManifest;
+use crate::tasks::output_lock::lock_output_dir;
+use crate::tasks::render_options::RenderOptions;
+use crate::templating::{TemplateContext, TemplateEngine};
 use async_trait::async_trait;
 use chrono::{Duration, Utc};
-use log::{info, warn};
-use std::path::Path;
+use log::{error, info, warn};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
 
 pub struct HomeGeneratorTask {
     template_engine: Arc<TemplateEngine>,
@@ -15,6 +19,23 @@ pub struct HomeGeneratorTask {
     trace_storage: Arc<TraceStorage>,
     log_storage: Arc<LogStorage>,
     output_dir: String,
+    template_name: String,
+    output_name: String,
+    write_error_page: bool,
+    render_options: RenderOptions,
+    dry_run: bool,
+    min_log_level: LogLevel,
+    max_traces_rendered: Option<usize>,
+    // Receives `generation_duration_ms` and `output_bytes` after every run.
+    metrics_sink: Option<Arc<MetricStorage>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DryRunPreview {
+    pub html_path: PathBuf,
+    pub html_content: String,
+    pub text_path: PathBuf,
+    pub text_content: String,
 }
 
 impl HomeGeneratorTask {
@@ -31,109 +52,452 @@ impl HomeGeneratorTask {
             trace_storage,
             log_storage,
             output_dir,
+            template_name: "home".to_string(),
+            output_name: "index".to_string(),
+            write_error_page: false,
+            render_options: RenderOptions::default(),
+            dry_run: false,
+            min_log_level: LogLevel::Debug,
+            max_traces_rendered: None,
+            metrics_sink: None,
         }
     }
 
-    async fn generate_site(&self) -> Result<()> {
-        info!("Generating home content");
+    pub fn builder() -> HomeGeneratorTaskBuilder {
+        HomeGeneratorTaskBuilder::new()
+    }
+
+    pub fn with_template_name(mut self, template_name: &str) -> Self {
+        self.template_name = template_name.to_string();
+        self
+    }
+
+    pub fn with_output_name(mut self, output_name: &str) -> Self {
+        self.output_name = output_name.to_string();
+        self
+    }
+
+    pub fn with_error_page(mut self, enabled: bool) -> Self {
+        self.write_error_page = enabled;
+        self
+    }
+
+    pub fn with_text_width(mut self, width: usize) -> Self {
+        self.render_options.text_width = width;
+        self
+    }
+
+    pub fn with_text_ascii_only(mut self, ascii_only: bool) -> Self {
+        self.render_options.text_ascii_only = ascii_only;
+        self
+    }
+
+    pub fn with_chart_size(mut self, width: u32, height: u32) -> Self {
+        self.render_options.chart_size = (width, height);
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn with_min_log_level(mut self, min_log_level: LogLevel) -> Self {
+        self.min_log_level = min_log_level;
+        self
+    }
+
+    // Only the slowest traces are rendered once the window holds more than this.
+    pub fn with_max_traces_rendered(mut self, max_traces: Option<usize>) -> Self {
+        self.max_traces_rendered = max_traces;
+        self
+    }
+
+    pub fn with_external_css(mut self, hashed: bool) -> Self {
+        self.render_options.external_css = Some(hashed);
+        self
+    }
+
+    pub fn with_metrics_sink(mut self, sink: Arc<MetricStorage>) -> Self {
+        self.metrics_sink = Some(sink);
+        self
+    }
+
+    pub async fn preview(&self) -> Result<DryRunPreview> {
         let (html_content, text_content) = self.generate_home().await?;
+        let output_path = Path::new(&self.output_dir);
+
+        Ok(DryRunPreview {
+            html_path: output_path.join(format!("{}.html", self.output_name)),
+            html_content,
+            text_path: output_path.join(format!("{}.txt", self.output_name)),
+            text_content,
+        })
+    }
+
+    async fn generate_site(&self) -> Result<()> {
+        if self.dry_run {
+            let preview = self.preview().await?;
+            info!(
+                "Dry run: would write {} bytes to {} and {} bytes to {}",
+                preview.html_content.len(),
+                preview.html_path.display(),
+                preview.text_content.len(),
+                preview.text_path.display()
+            );
+            return Ok(());
+        }
+
+        info!("Generating {} content", self.template_name);
+        let started = Instant::now();
+        let (html_content, text_content) = match self.generate_home().await {
+            Ok(content) => content,
+            Err(e) => {
+                if self.write_error_page {
+                    let _guard = lock_output_dir(&self.output_dir).await;
+                    if let Err(page_err) = self.generate_error_page(&e) {
+                        error!("Failed to write error page: {}", page_err);
+                    }
+                }
+                return Err(e);
+            }
+        };
 
         let output_path = Path::new(&self.output_dir);
         if !output_path.exists() {
             std::fs::create_dir_all(output_path)?;
         }
 
+        let _guard = lock_output_dir(output_path).await;
         self.template_engine.write_output(
             &html_content,
             &text_content,
             &self.output_dir,
-            "index",
+            &self.output_name,
         )?;
+        if self.render_options.external_css.is_some() {
+            self.render_options
+                .html_renderer()
+                .write_stylesheet(output_path)?;
+        }
+        BuildManifest::from_dir(output_path)?.write(output_path)?;
+
+        if let Some(sink) = &self.metrics_sink {
+            let output_bytes = html_content.len() + text_content.len();
+            sink.add(
+                Metric::new(
+                    "generation_duration_ms",
+                    started.elapsed().as_secs_f64() * 1000.0,
+                )
+                .with_unit("ms"),
+            )?;
+            sink.add(Metric::new("output_bytes", output_bytes as f64).with_unit("bytes"))?;
+        }
 
-        info!("Home generation completed");
+        info!("{} generation completed", self.template_name);
         Ok(())
     }
 
-    async fn generate_home(&self) -> Result<(String, String)> {
-        let now = Utc::now();
-        let one_hour_ago = now - Duration::hours(1);
+    fn generate_error_page(&self, err: &Error) -> Result<()> {
+        warn!("Writing error page after failed generation: {}", err);
 
-        let metrics = match self.metric_storage.get_by_time_range(one_hour_ago, now) {
-            Ok(metrics) => metrics,
-            Err(e) => {
-                warn!("Failed to retrieve metrics: {}", e);
-                Vec::new()
-            }
-        };
+        let context = TemplateContext::new().with_variable("title", "Error");
+        let message = err.to_string();
 
-        let traces = match self.trace_storage.get_by_time_range(one_hour_ago, now) {
-            Ok(traces) => traces,
-            Err(e) => {
-                warn!("Failed to retrieve traces: {}", e);
-                Vec::new()
-            }
-        };
+        let html_content = self.template_engine.render_error(
+            &message,
+            &context,
+            &self.render_options.html_renderer(),
+        )?;
+        let text_content = self.template_engine.render_error(
+            &message,
+            &context,
+            &self.render_options.text_renderer(),
+        )?;
 
-        let logs = match self.log_storage.get_by_time_range(one_hour_ago, now) {
-            Ok(logs) => logs,
-            Err(e) => {
-                warn!("Failed to retrieve logs: {}", e);
-                Vec::new()
-            }
-        };
+        self.template_engine.write_output(
+            &html_content,
+            &text_content,
+            &self.output_dir,
+            &self.output_name,
+        )
+    }
 
-        let template_context = self.create_context(metrics, traces, logs);
+    async fn generate_home(&self) -> Result<(String, String)> {
+        let template_context = build_context(
+            &self.metric_storage,
+            &self.trace_storage,
+            &self.log_storage,
+            self.min_log_level,
+            self.max_traces_rendered,
+        );
 
-        let html_renderer = HtmlRenderer::new();
-        let text_renderer = TextRenderer::new();
+        let html_renderer = self.render_options.html_renderer();
+        let text_renderer = self.render_options.text_renderer();
 
         let html_content =
             self.template_engine
-                .render("home", &template_context, &html_renderer)?;
+                .render(&self.template_name, &template_context, &html_renderer)?;
         let text_content =
             self.template_engine
-                .render("home", &template_context, &text_renderer)?;
+                .render(&self.template_name, &template_context, &text_renderer)?;
 
         Ok((html_content, text_content))
     }
+}
 
-    fn create_context(
-        &self,
-        metrics: Vec<Metric>,
-        traces: Vec<Trace>,
-        logs: Vec<LogEntry>,
-    ) -> TemplateContext {
-        let mut context = TemplateContext::new();
-
-        let current_time = Utc::now().to_rfc3339();
-        context = context.with_variable("current_time", &current_time);
+pub(crate) fn build_context(
+    metric_storage: &MetricStorage,
+    trace_storage: &TraceStorage,
+    log_storage: &LogStorage,
+    min_log_level: LogLevel,
+    max_traces: Option<usize>,
+) -> TemplateContext {
+    let now = Utc::now();
+    let one_hour_ago = now - Duration::hours(1);
+
+    let metrics = match metric_storage.get_by_time_range(one_hour_ago, now) {
+        Ok(metrics) => metrics,
+        Err(e) => {
+            warn!("Failed to retrieve metrics: {}", e);
+            Vec::new()
+        }
+    };
 
-        context = context.with_metrics(metrics);
-        context = context.with_traces(traces);
-        context = context.with_logs(logs);
+    let mut traces = match trace_storage.get_by_time_range(one_hour_ago, now) {
+        Ok(traces) => traces,
+        Err(e) => {
+            warn!("Failed to retrieve traces: {}", e);
+            Vec::new()
+        }
+    };
 
-        context = context.with_variable("title", "Max Teibel");
-        context = context.with_variable("hostname", "maxteibel-server");
+    let trace_total = traces.len();
+    if let Some(max_traces) = max_traces.filter(|max| trace_total > *max) {
+        traces.sort_by(slowest_first);
+        traces.truncate(max_traces);
+    }
 
-        let metric_count = format!("{}", context.metrics.len());
-        context = context.with_variable("metric_count", &metric_count);
+    let logs = match log_storage.get_by_time_range(one_hour_ago, now) {
+        Ok(mut logs) => {
+            logs.retain(|log| log.is_level_at_least(min_log_level));
+            logs
+        }
+        Err(e) => {
+            warn!("Failed to retrieve logs: {}", e);
+            Vec::new()
+        }
+    };
 
-        let trace_count = format!("{}", context.traces.len());
-        context = context.with_variable("trace_count", &trace_count);
+    let error_count = match log_storage.count_by_level(Some((one_hour_ago, now))) {
+        Ok(counts) => counts.get(&LogLevel::Error).copied().unwrap_or(0),
+        Err(e) => {
+            warn!("Failed to count logs by level: {}", e);
+            0
+        }
+    };
 
-        let log_count = format!("{}", context.logs.len());
-        context = context.with_variable("log_count", &log_count);
+    let context = create_context(metrics, traces, logs)
+        .with_variable("error_count", &error_count.to_string())
+        .with_variable("trace_total", &trace_total.to_string());
 
+    if error_count > 0 {
+        context.with_body_class("status-degraded")
+    } else {
         context
     }
 }
 
+fn create_context(
+    metrics: Vec<Metric>,
+    traces: Vec<Trace>,
+    logs: Vec<LogEntry>,
+) -> TemplateContext {
+    let mut context = TemplateContext::new();
+
+    let current_time = Utc::now().to_rfc3339();
+    context = context.with_variable("current_time", &current_time);
+
+    context = context.with_metrics(metrics);
+    context = context.with_traces(traces);
+    context = context.with_logs(logs);
+
+    context = context.with_variable("title", "Max Teibel");
+    context = context.with_variable("hostname", "maxteibel-server");
+
+    let metric_count = format!("{}", context.metrics.len());
+    context = context.with_variable("metric_count", &metric_count);
+
+    let trace_count = format!("{}", context.traces.len());
+    context = context.with_variable("trace_count", &trace_count);
+
+    let log_count = format!("{}", context.logs.len());
+    context = context.with_variable("log_count", &log_count);
+
+    context
+}
+
+#[derive(Default)]
+pub struct HomeGeneratorTaskBuilder {
+    template_engine: Option<Arc<TemplateEngine>>,
+    metric_storage: Option<Arc<MetricStorage>>,
+    trace_storage: Option<Arc<TraceStorage>>,
+    log_storage: Option<Arc<LogStorage>>,
+    output_dir: Option<String>,
+    template_name: Option<String>,
+    output_name: Option<String>,
+    write_error_page: bool,
+    text_width: Option<usize>,
+    text_ascii_only: bool,
+    chart_size: Option<(u32, u32)>,
+    dry_run: bool,
+    min_log_level: Option<LogLevel>,
+    max_traces_rendered: Option<usize>,
+    external_css: Option<bool>,
+    metrics_sink: Option<Arc<MetricStorage>>,
+}
+
+impl HomeGeneratorTaskBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_template_engine(mut self, template_engine: Arc<TemplateEngine>) -> Self {
+        self.template_engine = Some(template_engine);
+        self
+    }
+
+    pub fn with_metric_storage(mut self, metric_storage: Arc<MetricStorage>) -> Self {
+        self.metric_storage = Some(metric_storage);
+        self
+    }
+
+    pub fn with_trace_storage(mut self, trace_storage: Arc<TraceStorage>) -> Self {
+        self.trace_storage = Some(trace_storage);
+        self
+    }
+
+    pub fn with_log_storage(mut self, log_storage: Arc<LogStorage>) -> Self {
+        self.log_storage = Some(log_storage);
+        self
+    }
+
+    pub fn with_output_dir(mut self, output_dir: &str) -> Self {
+        self.output_dir = Some(output_dir.to_string());
+        self
+    }
+
+    pub fn with_template_name(mut self, template_name: &str) -> Self {
+        self.template_name = Some(template_name.to_string());
+        self
+    }
+
+    pub fn with_output_name(mut self, output_name: &str) -> Self {
+        self.output_name = Some(output_name.to_string());
+        self
+    }
+
+    pub fn with_error_page(mut self, enabled: bool) -> Self {
+        self.write_error_page = enabled;
+        self
+    }
+
+    pub fn with_text_width(mut self, width: usize) -> Self {
+        self.text_width = Some(width);
+        self
+    }
+
+    pub fn with_text_ascii_only(mut self, ascii_only: bool) -> Self {
+        self.text_ascii_only = ascii_only;
+        self
+    }
+
+    pub fn with_chart_size(mut self, width: u32, height: u32) -> Self {
+        self.chart_size = Some((width, height));
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn with_min_log_level(mut self, min_log_level: LogLevel) -> Self {
+        self.min_log_level = Some(min_log_level);
+        self
+    }
+
+    pub fn with_max_traces_rendered(mut self, max_traces: Option<usize>) -> Self {
+        self.max_traces_rendered = max_traces;
+        self
+    }
+
+    pub fn with_external_css(mut self, hashed: bool) -> Self {
+        self.external_css = Some(hashed);
+        self
+    }
+
+    pub fn with_metrics_sink(mut self, sink: Arc<MetricStorage>) -> Self {
+        self.metrics_sink = Some(sink);
+        self
+    }
+
+    pub fn build(self) -> Result<HomeGeneratorTask> {
+        let missing = |field: &str| {
+            Error::ConfigError(format!("HomeGeneratorTask is missing required {}", field))
+        };
+
+        let mut task = HomeGeneratorTask::new(
+            self.template_engine
+                .ok_or_else(|| missing("template_engine"))?,
+            self.metric_storage
+                .ok_or_else(|| missing("metric_storage"))?,
+            self.trace_storage.ok_or_else(|| missing("trace_storage"))?,
+            self.log_storage.ok_or_else(|| missing("log_storage"))?,
+            self.output_dir.ok_or_else(|| missing("output_dir"))?,
+        )
+        .with_error_page(self.write_error_page)
+        .with_text_ascii_only(self.text_ascii_only)
+        .with_dry_run(self.dry_run)
+        .with_max_traces_rendered(self.max_traces_rendered);
+
+        if let Some(template_name) = &self.template_name {
+            task = task.with_template_name(template_name);
+        }
+        if let Some(output_name) = &self.output_name {
+            task = task.with_output_name(output_name);
+        }
+        if let Some(width) = self.text_width {
+            task = task.with_text_width(width);
+        }
+        if let Some((width, height)) = self.chart_size {
+            task = task.with_chart_size(width, height);
+        }
+        if let Some(min_log_level) = self.min_log_level {
+            task = task.with_min_log_level(min_log_level);
+        }
+        if let Some(hashed) = self.external_css {
+            task = task.with_external_css(hashed);
+        }
+        if let Some(sink) = self.metrics_sink {
+            task = task.with_metrics_sink(sink);
+        }
+
+        Ok(task)
+    }
+}
+
 #[async_trait]
 impl Task for HomeGeneratorTask {
     fn name(&self) -> &str {
         "HomeGenerator"
     }
 
+    // Static assets land first so the build manifest always lists them.
+    fn depends_on(&self) -> Vec<&str> {
+        vec!["StaticAssets"]
+    }
+
     async fn execute(&self) -> Result<()> {
         self.generate_site().await
     }
@@ -142,7 +506,6 @@ impl Task for HomeGeneratorTask {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::LogLevel;
     use crate::storage::{LogStorage, MetricStorage, TraceStorage};
     use tempfile::TempDir;
 
@@ -154,6 +517,7 @@ mod tests {
         let template_content = r#"@heading{1}{Dashboard}
         
 @paragraph{Welcome to the system dashboard. Current status as of [[current_time]].}
+@paragraph{Errors in the last hour: [[error_count]]}
 
 @command{system status}
 @output{
@@ -186,6 +550,9 @@ mod tests {
 
         let log = LogEntry::new("Server started", LogLevel::Info, "app");
         log_storage.add(log).unwrap();
+        log_storage
+            .add(LogEntry::new("Disk full", LogLevel::Error, "app"))
+            .unwrap();
 
         let task = HomeGeneratorTask::new(
             template_engine,
@@ -210,6 +577,7 @@ mod tests {
         assert!(html_content.contains("Dashboard"));
         assert!(html_content.contains("CPU Usage"));
         assert!(html_content.contains("Server started"));
+        assert!(html_content.contains("Errors in the last hour: 1"));
 
         assert!(text_content.contains("Dashboard"));
         assert!(text_content.contains("CPU Usage"));
@@ -218,4 +586,400 @@ mod tests {
         let _ = std::fs::remove_file(html_path);
         let _ = std::fs::remove_file(text_path);
     }
+
+    #[tokio::test]
+    async fn test_error_page_on_render_failure() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        std::fs::write(
+            template_dir.path().join("home.tmpl"),
+            "@heading{1}{Broken}\n@unknown{directive}",
+        )
+        .unwrap();
+
+        let template_engine = Arc::new(TemplateEngine::new(template_dir.path()));
+        let html_path = output_dir.path().join("index.html");
+
+        let strict_task = HomeGeneratorTask::new(
+            template_engine.clone(),
+            Arc::new(MetricStorage::new()),
+            Arc::new(TraceStorage::new()),
+            Arc::new(LogStorage::new()),
+            output_dir.path().to_string_lossy().to_string(),
+        );
+
+        assert!(strict_task.execute().await.is_err());
+        assert!(!html_path.exists(), "Strict task should not write output");
+
+        let task = HomeGeneratorTask::new(
+            template_engine,
+            Arc::new(MetricStorage::new()),
+            Arc::new(TraceStorage::new()),
+            Arc::new(LogStorage::new()),
+            output_dir.path().to_string_lossy().to_string(),
+        )
+        .with_error_page(true);
+
+        assert!(task.execute().await.is_err());
+        assert!(html_path.exists(), "Error page was not written");
+
+        let html_content = std::fs::read_to_string(&html_path).unwrap();
+        assert!(html_content.contains("<!DOCTYPE html>"));
+        assert!(html_content.contains("Page generation failed"));
+        assert!(html_content.contains("Unknown directive @unknown"));
+        assert!(html_content.contains("<title>Error</title>"));
+    }
+
+    #[tokio::test]
+    async fn test_custom_template_name() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        std::fs::write(
+            template_dir.path().join("status.tmpl"),
+            "@heading{1}{Status Page}\n@paragraph{All systems operational.}",
+        )
+        .unwrap();
+
+        let task = HomeGeneratorTask::new(
+            Arc::new(TemplateEngine::new(template_dir.path())),
+            Arc::new(MetricStorage::new()),
+            Arc::new(TraceStorage::new()),
+            Arc::new(LogStorage::new()),
+            output_dir.path().to_string_lossy().to_string(),
+        )
+        .with_template_name("status")
+        .with_output_name("status");
+
+        task.execute().await.unwrap();
+
+        assert!(!output_dir.path().join("index.html").exists());
+
+        let html_content = std::fs::read_to_string(output_dir.path().join("status.html")).unwrap();
+        let text_content = std::fs::read_to_string(output_dir.path().join("status.txt")).unwrap();
+
+        assert!(html_content.contains("Status Page"));
+        assert!(html_content.contains("All systems operational."));
+        assert!(text_content.contains("Status Page"));
+    }
+
+    #[tokio::test]
+    async fn test_build_manifest() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        std::fs::write(template_dir.path().join("home.tmpl"), "@heading{1}{Home}").unwrap();
+
+        let task = HomeGeneratorTask::new(
+            Arc::new(TemplateEngine::new(template_dir.path())),
+            Arc::new(MetricStorage::new()),
+            Arc::new(TraceStorage::new()),
+            Arc::new(LogStorage::new()),
+            output_dir.path().to_string_lossy().to_string(),
+        );
+
+        task.execute().await.unwrap();
+
+        let manifest_json =
+            std::fs::read_to_string(output_dir.path().join("manifest.json")).unwrap();
+        let manifest: BuildManifest = serde_json::from_str(&manifest_json).unwrap();
+
+        let html = std::fs::read(output_dir.path().join("index.html")).unwrap();
+        let entry = manifest.get("index.html").unwrap();
+        assert_eq!(entry.size, html.len() as u64);
+        assert_eq!(entry.sha256, crate::tasks::manifest::sha256_hex(&html));
+        assert!(manifest.get("index.txt").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_metrics_sink() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        std::fs::write(template_dir.path().join("home.tmpl"), "@heading{1}{Home}").unwrap();
+
+        let sink = Arc::new(MetricStorage::new());
+        let task = HomeGeneratorTask::new(
+            Arc::new(TemplateEngine::new(template_dir.path())),
+            Arc::new(MetricStorage::new()),
+            Arc::new(TraceStorage::new()),
+            Arc::new(LogStorage::new()),
+            output_dir.path().to_string_lossy().to_string(),
+        )
+        .with_metrics_sink(sink.clone());
+
+        task.execute().await.unwrap();
+
+        let durations = sink.get_by_name("generation_duration_ms").unwrap();
+        assert_eq!(durations.len(), 1);
+        assert_eq!(durations[0].unit(), Some("ms"));
+
+        let html = std::fs::read(output_dir.path().join("index.html")).unwrap();
+        let text = std::fs::read(output_dir.path().join("index.txt")).unwrap();
+        let bytes = sink.get_by_name("output_bytes").unwrap();
+        assert_eq!(bytes[0].value, (html.len() + text.len()) as f64);
+    }
+
+    #[tokio::test]
+    async fn test_builder() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        std::fs::write(
+            template_dir.path().join("status.tmpl"),
+            "@heading{1}{Status}",
+        )
+        .unwrap();
+
+        let missing = HomeGeneratorTask::builder()
+            .with_template_engine(Arc::new(TemplateEngine::new(template_dir.path())))
+            .with_metric_storage(Arc::new(MetricStorage::new()))
+            .build();
+        assert!(missing
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("missing required trace_storage"));
+
+        let task = HomeGeneratorTask::builder()
+            .with_template_engine(Arc::new(TemplateEngine::new(template_dir.path())))
+            .with_metric_storage(Arc::new(MetricStorage::new()))
+            .with_trace_storage(Arc::new(TraceStorage::new()))
+            .with_log_storage(Arc::new(LogStorage::new()))
+            .with_output_dir(&output_dir.path().to_string_lossy())
+            .with_template_name("status")
+            .with_output_name("status")
+            .build()
+            .unwrap();
+
+        task.execute().await.unwrap();
+
+        let html_content = std::fs::read_to_string(output_dir.path().join("status.html")).unwrap();
+        assert!(html_content.contains("Status"));
+    }
+
+    #[tokio::test]
+    async fn test_text_width() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        let paragraph = "lorem ipsum dolor sit amet ".repeat(30);
+        std::fs::write(
+            template_dir.path().join("home.tmpl"),
+            format!("@paragraph{{{}}}", paragraph.trim()),
+        )
+        .unwrap();
+
+        let task = HomeGeneratorTask::new(
+            Arc::new(TemplateEngine::new(template_dir.path())),
+            Arc::new(MetricStorage::new()),
+            Arc::new(TraceStorage::new()),
+            Arc::new(LogStorage::new()),
+            output_dir.path().to_string_lossy().to_string(),
+        )
+        .with_text_width(120);
+
+        task.execute().await.unwrap();
+
+        let text_content = std::fs::read_to_string(output_dir.path().join("index.txt")).unwrap();
+        let widest = text_content
+            .lines()
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap();
+
+        assert!(
+            widest > 110,
+            "Expected lines close to 120 columns, got {}",
+            widest
+        );
+        assert!(widest <= 120);
+    }
+
+    #[tokio::test]
+    async fn test_text_ascii_only() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        std::fs::write(
+            template_dir.path().join("home.tmpl"),
+            "@frame{Status}{\n@paragraph{All systems operational.}\n}\n@table{@headers{Name|Value}@row{CPU|78.5}}",
+        )
+        .unwrap();
+
+        let task = HomeGeneratorTask::new(
+            Arc::new(TemplateEngine::new(template_dir.path())),
+            Arc::new(MetricStorage::new()),
+            Arc::new(TraceStorage::new()),
+            Arc::new(LogStorage::new()),
+            output_dir.path().to_string_lossy().to_string(),
+        )
+        .with_text_ascii_only(true);
+
+        task.execute().await.unwrap();
+
+        let text_content = std::fs::read_to_string(output_dir.path().join("index.txt")).unwrap();
+
+        assert!(text_content.contains('+'));
+        assert!(text_content.contains('-'));
+        assert!(text_content.contains('|'));
+        assert!(!text_content
+            .chars()
+            .any(|c| ('\u{2500}'..='\u{257F}').contains(&c)));
+    }
+
+    #[tokio::test]
+    async fn test_min_log_level() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        std::fs::write(template_dir.path().join("home.tmpl"), "@logs").unwrap();
+
+        let log_storage = Arc::new(LogStorage::new());
+        log_storage
+            .add(LogEntry::new("debug noise", LogLevel::Debug, "app"))
+            .unwrap();
+        log_storage
+            .add(LogEntry::new("server started", LogLevel::Info, "app"))
+            .unwrap();
+        log_storage
+            .add(LogEntry::new("cache miss", LogLevel::Warning, "cache"))
+            .unwrap();
+        log_storage
+            .add(LogEntry::new("disk full", LogLevel::Error, "app"))
+            .unwrap();
+
+        let task = HomeGeneratorTask::new(
+            Arc::new(TemplateEngine::new(template_dir.path())),
+            Arc::new(MetricStorage::new()),
+            Arc::new(TraceStorage::new()),
+            log_storage,
+            output_dir.path().to_string_lossy().to_string(),
+        )
+        .with_min_log_level(LogLevel::Warning);
+
+        task.execute().await.unwrap();
+
+        let text_content = std::fs::read_to_string(output_dir.path().join("index.txt")).unwrap();
+        assert!(text_content.contains("cache miss"));
+        assert!(text_content.contains("disk full"));
+        assert!(!text_content.contains("debug noise"));
+        assert!(!text_content.contains("server started"));
+    }
+
+    #[test]
+    fn test_max_traces_rendered() {
+        let trace_storage = TraceStorage::new();
+        for i in 0..50 {
+            trace_storage
+                .add(Trace::new(&format!("trace-{:02}", i), 100 + i))
+                .unwrap();
+        }
+
+        let context = build_context(
+            &MetricStorage::new(),
+            &trace_storage,
+            &LogStorage::new(),
+            LogLevel::Debug,
+            Some(10),
+        );
+
+        assert_eq!(context.traces.len(), 10);
+        assert_eq!(context.traces[0].name, "trace-49");
+        assert_eq!(context.variables["trace_count"], "10");
+        assert_eq!(context.variables["trace_total"], "50");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_generation_is_serialized() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        let filler = "@paragraph{padding padding padding padding}\n".repeat(200);
+        for name in ["alpha", "beta"] {
+            std::fs::write(
+                template_dir.path().join(format!("{}.tmpl", name)),
+                format!("@heading{{1}}{{{}}}\n{}", name, filler),
+            )
+            .unwrap();
+        }
+
+        let template_engine = Arc::new(TemplateEngine::new(template_dir.path()));
+        let create_task = |template_name: &str| {
+            Arc::new(
+                HomeGeneratorTask::new(
+                    template_engine.clone(),
+                    Arc::new(MetricStorage::new()),
+                    Arc::new(TraceStorage::new()),
+                    Arc::new(LogStorage::new()),
+                    output_dir.path().to_string_lossy().to_string(),
+                )
+                .with_template_name(template_name),
+            )
+        };
+
+        let alpha = create_task("alpha");
+        let beta = create_task("beta");
+
+        for _ in 0..20 {
+            let alpha = alpha.clone();
+            let beta = beta.clone();
+            let first = tokio::spawn(async move { alpha.generate_site().await });
+            let second = tokio::spawn(async move { beta.generate_site().await });
+            first.await.unwrap().unwrap();
+            second.await.unwrap().unwrap();
+
+            let html_content =
+                std::fs::read_to_string(output_dir.path().join("index.html")).unwrap();
+            let text_content =
+                std::fs::read_to_string(output_dir.path().join("index.txt")).unwrap();
+
+            let winner = if html_content.contains("alpha") {
+                "alpha"
+            } else {
+                "beta"
+            };
+            let loser = if winner == "alpha" { "beta" } else { "alpha" };
+            assert!(!html_content.contains(loser));
+            assert!(text_content.contains(winner));
+            assert!(!text_content.contains(loser));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_writes_nothing() {
+        let template_dir = TempDir::new().unwrap();
+        let output_root = TempDir::new().unwrap();
+        let output_dir = output_root.path().join("public");
+
+        std::fs::write(
+            template_dir.path().join("home.tmpl"),
+            "@heading{1}{Dashboard}\n@metrics",
+        )
+        .unwrap();
+
+        let metric_storage = Arc::new(MetricStorage::new());
+        metric_storage.add(Metric::new("CPU Usage", 75.5)).unwrap();
+
+        let task = HomeGeneratorTask::builder()
+            .with_template_engine(Arc::new(TemplateEngine::new(template_dir.path())))
+            .with_metric_storage(metric_storage)
+            .with_trace_storage(Arc::new(TraceStorage::new()))
+            .with_log_storage(Arc::new(LogStorage::new()))
+            .with_output_dir(&output_dir.to_string_lossy())
+            .with_dry_run(true)
+            .build()
+            .unwrap();
+
+        task.execute().await.unwrap();
+        assert!(!output_dir.exists());
+
+        let preview = task.preview().await.unwrap();
+        assert_eq!(preview.html_path, output_dir.join("index.html"));
+        assert_eq!(preview.text_path, output_dir.join("index.txt"));
+        assert!(preview.html_content.contains("Dashboard"));
+        assert!(preview.text_content.contains("CPU Usage"));
+        assert!(!output_dir.exists());
+    }
 }
@@ -0,0 +1,106 @@
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildManifest {
+    pub generated_at: DateTime<Utc>,
+    pub files: Vec<ManifestEntry>,
+}
+
+impl BuildManifest {
+    // Lists every file below `output_dir`, nested static assets included, by
+    // its `/`-separated path relative to the output directory.
+    pub fn from_dir<P: AsRef<Path>>(output_dir: P) -> Result<Self> {
+        let mut files = Vec::new();
+        collect_files(output_dir.as_ref(), "", &mut files)?;
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(Self {
+            generated_at: Utc::now(),
+            files,
+        })
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, output_dir: P) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(output_dir.as_ref().join(MANIFEST_FILE_NAME), json)?;
+        Ok(())
+    }
+
+    pub fn get(&self, path: &str) -> Option<&ManifestEntry> {
+        self.files.iter().find(|entry| entry.path == path)
+    }
+}
+
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<ManifestEntry>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            collect_files(&entry.path(), &format!("{}/", path), files)?;
+            continue;
+        }
+        if !file_type.is_file() || path == MANIFEST_FILE_NAME {
+            continue;
+        }
+
+        let content = fs::read(entry.path())?;
+        files.push(ManifestEntry {
+            path,
+            size: content.len() as u64,
+            sha256: sha256_hex(&content),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_manifest_from_dir() {
+        let output_dir = TempDir::new().unwrap();
+        fs::write(output_dir.path().join("index.html"), "<h1>Home</h1>").unwrap();
+        fs::write(output_dir.path().join("index.txt"), "Home").unwrap();
+        fs::create_dir(output_dir.path().join("assets")).unwrap();
+        fs::create_dir(output_dir.path().join("img")).unwrap();
+        fs::write(output_dir.path().join("img").join("favicon.ico"), "icon").unwrap();
+
+        let manifest = BuildManifest::from_dir(output_dir.path()).unwrap();
+        manifest.write(output_dir.path()).unwrap();
+
+        let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["img/favicon.ico", "index.html", "index.txt"]);
+        assert_eq!(manifest.get("index.txt").unwrap().size, 4);
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let rewritten = BuildManifest::from_dir(output_dir.path()).unwrap();
+        assert!(rewritten.get(MANIFEST_FILE_NAME).is_none());
+    }
+}
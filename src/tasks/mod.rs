@@ -1,3 +1,15 @@
 pub mod home_generator;
+pub mod manifest;
+mod output_lock;
+pub mod persist;
+mod render_options;
+pub mod retention;
+pub mod site_generator;
+pub mod static_assets;
 
-pub use home_generator::HomeGeneratorTask;
+pub use home_generator::{DryRunPreview, HomeGeneratorTask, HomeGeneratorTaskBuilder};
+pub use manifest::BuildManifest;
+pub use persist::PersistTask;
+pub use retention::RetentionTask;
+pub use site_generator::SiteGeneratorTask;
+pub use static_assets::StaticAssetsTask;
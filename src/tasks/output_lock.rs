@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+static OUTPUT_LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<AsyncMutex<()>>>>> = OnceLock::new();
+
+pub(crate) async fn lock_output_dir<P: AsRef<Path>>(output_dir: P) -> OwnedMutexGuard<()> {
+    let output_dir = output_dir.as_ref();
+    let key = output_dir
+        .canonicalize()
+        .unwrap_or_else(|_| output_dir.to_path_buf());
+
+    let lock = {
+        let mut locks = OUTPUT_LOCKS
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        locks.entry(key).or_default().clone()
+    };
+
+    lock.lock_owned().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_lock_is_shared_across_path_spellings() {
+        let output_dir = TempDir::new().unwrap();
+        let direct = output_dir.path().to_path_buf();
+        let indirect = output_dir.path().join(".");
+
+        let guard = lock_output_dir(&direct).await;
+        let pending = tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            lock_output_dir(&indirect),
+        )
+        .await;
+        assert!(pending.is_err(), "Second lock should wait for the first");
+
+        drop(guard);
+        let _guard = lock_output_dir(&indirect).await;
+    }
+}
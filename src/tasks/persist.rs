@@ -0,0 +1,134 @@
+use crate::error::Result;
+use crate::scheduler::Task;
+use crate::storage::{LogStorage, MetricStorage, TraceStorage};
+use async_trait::async_trait;
+use log::info;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// Periodically saves the configured storages so their contents survive a
+// restart. Pair with the storages' `load_from_file` at startup.
+#[derive(Default)]
+pub struct PersistTask {
+    metrics: Option<(Arc<MetricStorage>, PathBuf)>,
+    traces: Option<(Arc<TraceStorage>, PathBuf)>,
+    logs: Option<(Arc<LogStorage>, PathBuf)>,
+}
+
+impl PersistTask {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_metric_storage<P: Into<PathBuf>>(
+        mut self,
+        storage: Arc<MetricStorage>,
+        path: P,
+    ) -> Self {
+        self.metrics = Some((storage, path.into()));
+        self
+    }
+
+    pub fn with_trace_storage<P: Into<PathBuf>>(
+        mut self,
+        storage: Arc<TraceStorage>,
+        path: P,
+    ) -> Self {
+        self.traces = Some((storage, path.into()));
+        self
+    }
+
+    pub fn with_log_storage<P: Into<PathBuf>>(mut self, storage: Arc<LogStorage>, path: P) -> Self {
+        self.logs = Some((storage, path.into()));
+        self
+    }
+
+    fn persist(&self) -> Result<()> {
+        if let Some((storage, path)) = &self.metrics {
+            let count = storage.save_to_file(path)?;
+            info!("Persisted {} metrics to {}", count, path.display());
+        }
+
+        if let Some((storage, path)) = &self.traces {
+            let count = storage.save_to_file(path)?;
+            info!("Persisted {} traces to {}", count, path.display());
+        }
+
+        if let Some((storage, path)) = &self.logs {
+            let count = storage.save_to_file(path)?;
+            info!("Persisted {} logs to {}", count, path.display());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Task for PersistTask {
+    fn name(&self) -> &str {
+        "Persist"
+    }
+
+    async fn execute(&self) -> Result<()> {
+        self.persist()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LogEntry, LogLevel, Metric, Trace};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_persist_task_round_trip() {
+        let dir = TempDir::new().unwrap();
+
+        let metric_storage = Arc::new(MetricStorage::new());
+        metric_storage.add(Metric::new("CPU Usage", 78.5)).unwrap();
+        metric_storage.add(Metric::new("Memory", 4.2)).unwrap();
+
+        let trace_storage = Arc::new(TraceStorage::new());
+        trace_storage.add(Trace::new("API Request", 157)).unwrap();
+
+        let log_storage = Arc::new(LogStorage::new());
+        for message in ["Server started", "Cache miss", "Disk full"] {
+            log_storage
+                .add(LogEntry::new(message, LogLevel::Info, "app"))
+                .unwrap();
+        }
+
+        let task = PersistTask::new()
+            .with_metric_storage(metric_storage.clone(), dir.path().join("metrics.json"))
+            .with_trace_storage(trace_storage.clone(), dir.path().join("traces.json"))
+            .with_log_storage(log_storage.clone(), dir.path().join("logs.json"));
+        task.execute().await.unwrap();
+
+        let metrics = MetricStorage::new();
+        let traces = TraceStorage::new();
+        let logs = LogStorage::new();
+        assert_eq!(
+            metrics
+                .load_from_file(dir.path().join("metrics.json"))
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            traces
+                .load_from_file(dir.path().join("traces.json"))
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            logs.load_from_file(dir.path().join("logs.json")).unwrap(),
+            3
+        );
+
+        assert_eq!(
+            metrics.get_all().unwrap(),
+            metric_storage.get_all().unwrap()
+        );
+        assert_eq!(logs.get_all().unwrap(), log_storage.get_all().unwrap());
+        assert!(!dir.path().join("metrics.json.tmp").exists());
+    }
+}
@@ -0,0 +1,41 @@
+use crate::templating::html_renderer::{DEFAULT_CHART_HEIGHT, DEFAULT_CHART_WIDTH};
+use crate::templating::text_renderer::DEFAULT_TERMINAL_WIDTH;
+use crate::templating::{HtmlRenderer, TextRenderer};
+
+// Renderer settings shared by the generator tasks, so every page of a site
+// renders the same way.
+#[derive(Debug, Clone)]
+pub(crate) struct RenderOptions {
+    pub(crate) text_width: usize,
+    pub(crate) text_ascii_only: bool,
+    pub(crate) chart_size: (u32, u32),
+    // `Some(hashed)` writes a standalone stylesheet instead of inlining CSS.
+    pub(crate) external_css: Option<bool>,
+}
+
+impl RenderOptions {
+    pub(crate) fn html_renderer(&self) -> HtmlRenderer {
+        let renderer = HtmlRenderer::new().with_chart_size(self.chart_size.0, self.chart_size.1);
+        match self.external_css {
+            Some(hashed) => renderer.with_external_css(hashed),
+            None => renderer,
+        }
+    }
+
+    pub(crate) fn text_renderer(&self) -> TextRenderer {
+        TextRenderer::new()
+            .with_width(self.text_width)
+            .with_ascii_only(self.text_ascii_only)
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            text_width: DEFAULT_TERMINAL_WIDTH,
+            text_ascii_only: false,
+            chart_size: (DEFAULT_CHART_WIDTH, DEFAULT_CHART_HEIGHT),
+            external_css: None,
+        }
+    }
+}
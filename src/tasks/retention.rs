@@ -0,0 +1,112 @@
+use crate::error::Result;
+use crate::scheduler::Task;
+use crate::storage::{LogStorage, MetricStorage, TraceStorage};
+use async_trait::async_trait;
+use chrono::Duration;
+use log::info;
+use std::sync::Arc;
+
+// Drops metrics, traces and logs older than `max_age` on every run so the
+// storages don't grow without bound.
+pub struct RetentionTask {
+    metric_storage: Arc<MetricStorage>,
+    trace_storage: Arc<TraceStorage>,
+    log_storage: Arc<LogStorage>,
+    max_age: Duration,
+}
+
+impl RetentionTask {
+    pub fn new(
+        metric_storage: Arc<MetricStorage>,
+        trace_storage: Arc<TraceStorage>,
+        log_storage: Arc<LogStorage>,
+        max_age: Duration,
+    ) -> Self {
+        Self {
+            metric_storage,
+            trace_storage,
+            log_storage,
+            max_age,
+        }
+    }
+}
+
+#[async_trait]
+impl Task for RetentionTask {
+    fn name(&self) -> &str {
+        "Retention"
+    }
+
+    async fn execute(&self) -> Result<()> {
+        let metrics = self.metric_storage.prune_older_than(self.max_age)?;
+        let traces = self.trace_storage.prune_older_than(self.max_age)?;
+        let logs = self.log_storage.prune_older_than(self.max_age)?;
+
+        info!(
+            "Pruned {} metrics, {} traces and {} logs older than {}h",
+            metrics,
+            traces,
+            logs,
+            self.max_age.num_hours()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LogEntry, LogLevel, Metric, Trace};
+    use chrono::Utc;
+
+    #[tokio::test]
+    async fn test_retention_task_prunes_all_storages() {
+        let old = Utc::now() - Duration::days(2);
+
+        let metric_storage = Arc::new(MetricStorage::new());
+        metric_storage
+            .add(Metric::with_timestamp("CPU Usage", 50.0, old))
+            .unwrap();
+        metric_storage.add(Metric::new("CPU Usage", 78.5)).unwrap();
+
+        let trace_storage = Arc::new(TraceStorage::new());
+        trace_storage
+            .add(Trace::with_times(
+                "Old Request",
+                old,
+                old + Duration::seconds(1),
+            ))
+            .unwrap();
+        trace_storage.add(Trace::new("API Request", 157)).unwrap();
+
+        let log_storage = Arc::new(LogStorage::new());
+        log_storage
+            .add(LogEntry::with_timestamp(
+                "stale",
+                LogLevel::Info,
+                "app",
+                old,
+            ))
+            .unwrap();
+        log_storage
+            .add(LogEntry::new("fresh", LogLevel::Info, "app"))
+            .unwrap();
+
+        RetentionTask::new(
+            metric_storage.clone(),
+            trace_storage.clone(),
+            log_storage.clone(),
+            Duration::hours(24),
+        )
+        .execute()
+        .await
+        .unwrap();
+
+        assert_eq!(metric_storage.get_all().unwrap()[0].value, 78.5);
+        assert_eq!(trace_storage.get_all().unwrap()[0].name, "API Request");
+        assert_eq!(log_storage.get_all().unwrap()[0].message, "fresh");
+        assert_eq!(metric_storage.count().unwrap(), 1);
+        assert_eq!(trace_storage.count().unwrap(), 1);
+        assert_eq!(log_storage.count().unwrap(), 1);
+    }
+}
@@ -0,0 +1,304 @@
+use crate::error::{Error, Result};
+use crate::models::LogLevel;
+use crate::scheduler::Task;
+use crate::storage::{LogStorage, MetricStorage, TraceStorage};
+use crate::tasks::home_generator::build_context;
+use crate::tasks::manifest::BuildManifest;
+use crate::tasks::output_lock::lock_output_dir;
+use crate::tasks::render_options::RenderOptions;
+use crate::templating::{HtmlRenderer, TemplateContext, TemplateEngine, TextRenderer};
+use async_trait::async_trait;
+use log::{error, info};
+use std::path::Path;
+use std::sync::Arc;
+
+pub struct SiteGeneratorTask {
+    template_engine: Arc<TemplateEngine>,
+    metric_storage: Arc<MetricStorage>,
+    trace_storage: Arc<TraceStorage>,
+    log_storage: Arc<LogStorage>,
+    output_dir: String,
+    pages: Vec<(String, String)>,
+    render_options: RenderOptions,
+    min_log_level: LogLevel,
+    max_traces_rendered: Option<usize>,
+}
+
+impl SiteGeneratorTask {
+    pub fn new(
+        template_engine: Arc<TemplateEngine>,
+        metric_storage: Arc<MetricStorage>,
+        trace_storage: Arc<TraceStorage>,
+        log_storage: Arc<LogStorage>,
+        output_dir: String,
+        pages: Vec<(String, String)>,
+    ) -> Self {
+        Self {
+            template_engine,
+            metric_storage,
+            trace_storage,
+            log_storage,
+            output_dir,
+            pages,
+            render_options: RenderOptions::default(),
+            min_log_level: LogLevel::Debug,
+            max_traces_rendered: None,
+        }
+    }
+
+    pub fn with_text_width(mut self, width: usize) -> Self {
+        self.render_options.text_width = width;
+        self
+    }
+
+    pub fn with_text_ascii_only(mut self, ascii_only: bool) -> Self {
+        self.render_options.text_ascii_only = ascii_only;
+        self
+    }
+
+    pub fn with_chart_size(mut self, width: u32, height: u32) -> Self {
+        self.render_options.chart_size = (width, height);
+        self
+    }
+
+    pub fn with_external_css(mut self, hashed: bool) -> Self {
+        self.render_options.external_css = Some(hashed);
+        self
+    }
+
+    pub fn with_min_log_level(mut self, min_log_level: LogLevel) -> Self {
+        self.min_log_level = min_log_level;
+        self
+    }
+
+    pub fn with_max_traces_rendered(mut self, max_traces: Option<usize>) -> Self {
+        self.max_traces_rendered = max_traces;
+        self
+    }
+
+    async fn generate_site(&self) -> Result<()> {
+        info!("Generating {} pages", self.pages.len());
+
+        let context = build_context(
+            &self.metric_storage,
+            &self.trace_storage,
+            &self.log_storage,
+            self.min_log_level,
+            self.max_traces_rendered,
+        );
+
+        let html_renderer = self.render_options.html_renderer();
+        let text_renderer = self.render_options.text_renderer();
+        let mut failures = Vec::new();
+
+        let _guard = lock_output_dir(&self.output_dir).await;
+        for (template_name, output_name) in &self.pages {
+            if let Err(e) = self.generate_page(
+                template_name,
+                output_name,
+                &context,
+                &html_renderer,
+                &text_renderer,
+            ) {
+                error!("Failed to generate page '{}': {}", template_name, e);
+                failures.push(format!("{}: {}", template_name, e));
+            }
+        }
+
+        let output_path = Path::new(&self.output_dir);
+        if output_path.exists() {
+            if self.render_options.external_css.is_some() {
+                html_renderer.write_stylesheet(output_path)?;
+            }
+            BuildManifest::from_dir(output_path)?.write(output_path)?;
+        }
+
+        if !failures.is_empty() {
+            return Err(Error::GenerationError(format!(
+                "{} of {} pages failed: {}",
+                failures.len(),
+                self.pages.len(),
+                failures.join("; ")
+            )));
+        }
+
+        info!("Site generation completed");
+        Ok(())
+    }
+
+    fn generate_page(
+        &self,
+        template_name: &str,
+        output_name: &str,
+        context: &TemplateContext,
+        html_renderer: &HtmlRenderer,
+        text_renderer: &TextRenderer,
+    ) -> Result<()> {
+        let html_content = self
+            .template_engine
+            .render(template_name, context, html_renderer)?;
+        let text_content = self
+            .template_engine
+            .render(template_name, context, text_renderer)?;
+
+        self.template_engine.write_output(
+            &html_content,
+            &text_content,
+            &self.output_dir,
+            output_name,
+        )
+    }
+}
+
+#[async_trait]
+impl Task for SiteGeneratorTask {
+    fn name(&self) -> &str {
+        "SiteGenerator"
+    }
+
+    // Static assets land first so the build manifest always lists them.
+    fn depends_on(&self) -> Vec<&str> {
+        vec!["StaticAssets"]
+    }
+
+    async fn execute(&self) -> Result<()> {
+        self.generate_site().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LogEntry, Trace};
+    use tempfile::TempDir;
+
+    fn create_task(
+        template_dir: &TempDir,
+        output_dir: &TempDir,
+        pages: &[(&str, &str)],
+    ) -> SiteGeneratorTask {
+        SiteGeneratorTask::new(
+            Arc::new(TemplateEngine::new(template_dir.path())),
+            Arc::new(MetricStorage::new()),
+            Arc::new(TraceStorage::new()),
+            Arc::new(LogStorage::new()),
+            output_dir.path().to_string_lossy().to_string(),
+            pages
+                .iter()
+                .map(|(template, output)| (template.to_string(), output.to_string()))
+                .collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_generates_multiple_pages() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        std::fs::write(template_dir.path().join("home.tmpl"), "@heading{1}{Home}").unwrap();
+        std::fs::write(template_dir.path().join("about.tmpl"), "@heading{1}{About}").unwrap();
+
+        let task = create_task(
+            &template_dir,
+            &output_dir,
+            &[("home", "index"), ("about", "about")],
+        );
+
+        task.execute().await.unwrap();
+
+        for page in ["index", "about"] {
+            assert!(output_dir.path().join(format!("{}.html", page)).exists());
+            assert!(output_dir.path().join(format!("{}.txt", page)).exists());
+        }
+
+        let about = std::fs::read_to_string(output_dir.path().join("about.html")).unwrap();
+        assert!(about.contains("About"));
+
+        let manifest = BuildManifest::from_dir(output_dir.path()).unwrap();
+        assert!(manifest.get("index.html").is_some());
+        assert!(manifest.get("about.html").is_some());
+        assert!(output_dir.path().join("manifest.json").exists());
+    }
+
+    #[tokio::test]
+    async fn test_pages_use_configured_renderers() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        std::fs::write(
+            template_dir.path().join("about.tmpl"),
+            "@metric{CPU}{42}{%}{0}",
+        )
+        .unwrap();
+
+        let task = create_task(&template_dir, &output_dir, &[("about", "about")])
+            .with_text_width(40)
+            .with_external_css(false);
+        task.execute().await.unwrap();
+
+        let text = std::fs::read_to_string(output_dir.path().join("about.txt")).unwrap();
+        let line = text.lines().find(|line| line.starts_with("CPU:")).unwrap();
+        assert_eq!(line.chars().count(), 39);
+
+        let html = std::fs::read_to_string(output_dir.path().join("about.html")).unwrap();
+        assert!(html.contains("<link rel=\"stylesheet\" href=\"terminal.css\">"));
+        assert!(output_dir.path().join("terminal.css").exists());
+    }
+
+    #[tokio::test]
+    async fn test_pages_apply_log_and_trace_limits() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        std::fs::write(template_dir.path().join("ops.tmpl"), "@logs\n@traces").unwrap();
+
+        let log_storage = Arc::new(LogStorage::new());
+        log_storage
+            .add(LogEntry::new("Cache warmed", LogLevel::Debug, "app"))
+            .unwrap();
+        log_storage
+            .add(LogEntry::new("Disk almost full", LogLevel::Warning, "app"))
+            .unwrap();
+        let trace_storage = Arc::new(TraceStorage::new());
+        trace_storage.add(Trace::new("fast-request", 10)).unwrap();
+        trace_storage.add(Trace::new("slow-request", 900)).unwrap();
+
+        let task = SiteGeneratorTask::new(
+            Arc::new(TemplateEngine::new(template_dir.path())),
+            Arc::new(MetricStorage::new()),
+            trace_storage,
+            log_storage,
+            output_dir.path().to_string_lossy().to_string(),
+            vec![("ops".to_string(), "ops".to_string())],
+        )
+        .with_min_log_level(LogLevel::Warning)
+        .with_max_traces_rendered(Some(1));
+        task.execute().await.unwrap();
+
+        let text = std::fs::read_to_string(output_dir.path().join("ops.txt")).unwrap();
+        assert!(text.contains("Disk almost full"));
+        assert!(!text.contains("Cache warmed"));
+        assert!(text.contains("slow-request"));
+        assert!(!text.contains("fast-request"));
+    }
+
+    #[tokio::test]
+    async fn test_page_failure_does_not_abort_others() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+
+        std::fs::write(template_dir.path().join("about.tmpl"), "@heading{1}{About}").unwrap();
+
+        let task = create_task(
+            &template_dir,
+            &output_dir,
+            &[("missing", "missing"), ("about", "about")],
+        );
+
+        let err = task.execute().await.unwrap_err().to_string();
+        assert!(err.contains("1 of 2 pages failed"));
+        assert!(err.contains("missing"));
+
+        assert!(output_dir.path().join("about.html").exists());
+        assert!(!output_dir.path().join("missing.html").exists());
+    }
+}
@@ -0,0 +1,132 @@
+use crate::error::{Error, Result};
+use crate::scheduler::Task;
+use crate::tasks::manifest::sha256_hex;
+use crate::tasks::output_lock::lock_output_dir;
+use async_trait::async_trait;
+use log::{debug, info};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Mirrors a static-assets directory (favicon, logos, fonts) into the output
+// directory so generated pages can reference them by absolute path.
+pub struct StaticAssetsTask {
+    static_dir: PathBuf,
+    output_dir: PathBuf,
+}
+
+impl StaticAssetsTask {
+    pub fn new<S: Into<PathBuf>, O: Into<PathBuf>>(static_dir: S, output_dir: O) -> Self {
+        Self {
+            static_dir: static_dir.into(),
+            output_dir: output_dir.into(),
+        }
+    }
+
+    // Copies every file under the static directory, preserving structure.
+    // Files whose content already matches the destination are left alone.
+    // Returns the number of files written.
+    pub fn copy_assets(&self) -> Result<usize> {
+        copy_dir(&self.static_dir, &self.output_dir)
+    }
+}
+
+fn copy_dir(source: &Path, destination: &Path) -> Result<usize> {
+    fs::create_dir_all(destination)?;
+
+    let mut copied = 0;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let target = destination.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            copied += copy_dir(&entry.path(), &target)?;
+            continue;
+        }
+
+        let content = fs::read(entry.path())?;
+        let unchanged = fs::read(&target)
+            .map(|existing| sha256_hex(&existing) == sha256_hex(&content))
+            .unwrap_or(false);
+        if unchanged {
+            debug!("Static asset {} unchanged, skipping", target.display());
+            continue;
+        }
+
+        fs::write(&target, content)?;
+        copied += 1;
+    }
+
+    Ok(copied)
+}
+
+#[async_trait]
+impl Task for StaticAssetsTask {
+    fn name(&self) -> &str {
+        "StaticAssets"
+    }
+
+    // Holds the output lock like the generators, and copies on the blocking
+    // pool since the walk is plain `std::fs` I/O.
+    async fn execute(&self) -> Result<()> {
+        let _guard = lock_output_dir(&self.output_dir).await;
+        let static_dir = self.static_dir.clone();
+        let output_dir = self.output_dir.clone();
+        let copied = tokio::task::spawn_blocking(move || copy_dir(&static_dir, &output_dir))
+            .await
+            .map_err(|e| Error::GenerationError(format!("Static asset copy failed: {}", e)))??;
+        info!(
+            "Copied {} static assets from {} to {}",
+            copied,
+            self.static_dir.display(),
+            self.output_dir.display()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_static_assets_copied_into_output() {
+        let static_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        fs::create_dir(static_dir.path().join("img")).unwrap();
+        fs::write(static_dir.path().join("img").join("favicon.ico"), b"icon").unwrap();
+
+        let task = StaticAssetsTask::new(static_dir.path(), output_dir.path());
+        task.execute().await.unwrap();
+
+        let copied = output_dir.path().join("img").join("favicon.ico");
+        assert_eq!(fs::read(&copied).unwrap(), b"icon");
+        assert_eq!(task.copy_assets().unwrap(), 0);
+
+        fs::write(
+            static_dir.path().join("img").join("favicon.ico"),
+            b"new icon",
+        )
+        .unwrap();
+        assert_eq!(task.copy_assets().unwrap(), 1);
+        assert_eq!(fs::read(&copied).unwrap(), b"new icon");
+    }
+
+    #[tokio::test]
+    async fn test_static_assets_wait_for_output_lock() {
+        let static_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        fs::write(static_dir.path().join("favicon.ico"), b"icon").unwrap();
+
+        let task = StaticAssetsTask::new(static_dir.path(), output_dir.path());
+        let guard = lock_output_dir(output_dir.path()).await;
+        let pending =
+            tokio::time::timeout(std::time::Duration::from_millis(50), task.execute()).await;
+        assert!(pending.is_err(), "Copy should wait for the output lock");
+        assert!(!output_dir.path().join("favicon.ico").exists());
+
+        drop(guard);
+        task.execute().await.unwrap();
+        assert!(output_dir.path().join("favicon.ico").exists());
+    }
+}
@@ -1,11 +1,32 @@
 use crate::error::{Error, Result};
 use crate::models::{LogEntry, Metric, Trace};
-use crate::templating::renderer::{Block, Renderer, TemplateData};
-use crate::templating::template::Template;
-use std::collections::HashMap;
+use crate::templating::html_renderer::HtmlRenderer;
+use crate::templating::json_renderer::JsonRenderer;
+use crate::templating::null_renderer::NullRenderer;
+use crate::templating::renderer::{
+    format_metric_value, Block, MetricThresholds, Renderer, TemplateData, WaterfallSpan,
+};
+use crate::templating::template::{find_placeholders, Template, DEFAULT_MAX_DEPTH};
+use crate::templating::text_renderer::TextRenderer;
+use chrono::{DateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OutputFormat {
+    Html,
+    Text,
+    Json,
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct TemplateContext {
@@ -14,6 +35,9 @@ pub struct TemplateContext {
     pub logs: Vec<LogEntry>,
     pub traces: Vec<Trace>,
     pub data: HashMap<String, serde_json::Value>,
+    pub blocks: HashMap<String, Vec<Block>>,
+    pub body_classes: Vec<String>,
+    pub title: Option<String>,
 }
 
 impl TemplateContext {
@@ -50,11 +74,77 @@ impl TemplateContext {
         self.data.insert(key.into(), value);
         self
     }
+
+    pub fn with_blocks<S: Into<String>>(mut self, slot: S, blocks: Vec<Block>) -> Self {
+        self.blocks.insert(slot.into(), blocks);
+        self
+    }
+
+    pub fn with_body_class<S: Into<String>>(mut self, class: S) -> Self {
+        self.body_classes.push(class.into());
+        self
+    }
+
+    // Shown in the page `<title>` and text header instead of the template name.
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CompiledTemplate {
+    source_modified: SystemTime,
+    template: Template,
+}
+
+#[derive(Debug, Default)]
+pub struct PreloadReport {
+    pub loaded: Vec<String>,
+    pub failed: Vec<(String, Error)>,
+}
+
+pub type PostProcessor = Box<dyn Fn(OutputFormat, String) -> Result<String> + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    // Single pass; existing "\r\n" pairs are kept as they are.
+    pub fn apply(self, content: String) -> String {
+        match self {
+            LineEnding::Lf => content,
+            LineEnding::CrLf => {
+                let mut converted = String::with_capacity(content.len());
+                let mut previous = None;
+                for c in content.chars() {
+                    if c == '\n' && previous != Some('\r') {
+                        converted.push('\r');
+                    }
+                    converted.push(c);
+                    previous = Some(c);
+                }
+                converted
+            }
+        }
+    }
 }
 
 pub struct TemplateEngine {
-    template_dir: PathBuf,
+    template_dirs: Vec<PathBuf>,
     template_cache: Arc<RwLock<HashMap<String, Template>>>,
+    registered_templates: RwLock<HashMap<String, Template>>,
+    disk_cache: bool,
+    gzip_output: bool,
+    strict_validation: bool,
+    post_processors: Vec<PostProcessor>,
+    line_ending: LineEnding,
+    max_depth: usize,
+    parse_count: AtomicUsize,
 }
 
 impl TemplateEngine {
@@ -62,12 +152,114 @@ impl TemplateEngine {
         let template_dir = template_dir.as_ref().to_path_buf();
 
         Self {
-            template_dir,
+            template_dirs: vec![template_dir],
             template_cache: Arc::new(RwLock::new(HashMap::new())),
+            registered_templates: RwLock::new(HashMap::new()),
+            disk_cache: false,
+            gzip_output: false,
+            strict_validation: false,
+            post_processors: Vec::new(),
+            line_ending: LineEnding::default(),
+            max_depth: DEFAULT_MAX_DEPTH,
+            parse_count: AtomicUsize::new(0),
+        }
+    }
+
+    // Directories are searched in order; the first one containing a template wins.
+    pub fn with_search_path(mut self, template_dirs: Vec<PathBuf>) -> Self {
+        self.template_dirs = template_dirs;
+        self
+    }
+
+    pub fn with_disk_cache(mut self, enabled: bool) -> Self {
+        self.disk_cache = enabled;
+        self
+    }
+
+    pub fn with_gzip_output(mut self, enabled: bool) -> Self {
+        self.gzip_output = enabled;
+        self
+    }
+
+    // Runs Template::validate on every template as it is registered or loaded.
+    pub fn with_strict_validation(mut self, enabled: bool) -> Self {
+        self.strict_validation = enabled;
+        self
+    }
+
+    // Processors run on the final output of renderers that report an output
+    // format, in the order they were registered.
+    pub fn with_post_processor(mut self, processor: PostProcessor) -> Self {
+        self.post_processors.push(processor);
+        self
+    }
+
+    // Applied to text and HTML output after the post-processors; JSON is left alone.
+    pub fn with_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    fn post_process<R: Renderer>(&self, renderer: &R, content: String) -> Result<String> {
+        let format = match renderer.output_format() {
+            Some(format) => format,
+            None => return Ok(content),
+        };
+
+        let content = self
+            .post_processors
+            .iter()
+            .try_fold(content, |content, processor| processor(format, content))?;
+        match format {
+            OutputFormat::Html | OutputFormat::Text => Ok(self.line_ending.apply(content)),
+            OutputFormat::Json => Ok(content),
         }
     }
 
+    // Limits @frame/@output nesting both when parsing and when expanding blocks.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn parse_count(&self) -> usize {
+        self.parse_count.load(Ordering::SeqCst)
+    }
+
+    // Registered templates live outside the file cache so clear_cache() keeps them.
+    pub fn register_template(&self, name: &str, content: &str) -> Result<()> {
+        let template = Template::from_string_with_max_depth(name, content, self.max_depth)?;
+        self.parse_count.fetch_add(1, Ordering::SeqCst);
+        if self.strict_validation {
+            template.validate()?;
+        }
+
+        let mut registered = self.registered_templates.write().map_err(|e| {
+            Error::TemplateError(
+                format!(
+                    "Failed to acquire write lock on registered templates: {}",
+                    e
+                )
+                .into(),
+            )
+        })?;
+        registered.insert(name.to_string(), template);
+        Ok(())
+    }
+
     pub fn load_template(&self, name: &str) -> Result<Template> {
+        {
+            let registered = self.registered_templates.read().map_err(|e| {
+                Error::TemplateError(
+                    format!("Failed to acquire read lock on registered templates: {}", e).into(),
+                )
+            })?;
+
+            if let Some(template) = registered.get(name) {
+                return Ok(template.clone());
+            }
+        }
+
         {
             let cache = self.template_cache.read().map_err(|e| {
                 Error::TemplateError(
@@ -80,14 +272,33 @@ impl TemplateEngine {
             }
         }
 
-        let template_path = self.template_dir.join(format!("{}.tmpl", name));
-        if !template_path.exists() {
-            return Err(Error::TemplateError(
-                format!("Template '{}' not found", name).into(),
-            ));
-        }
+        let file_name = format!("{}.tmpl", name);
+        let template_path = self
+            .template_dirs
+            .iter()
+            .map(|dir| dir.join(&file_name))
+            .find(|path| path.exists())
+            .ok_or_else(|| Error::TemplateError(format!("Template '{}' not found", name).into()))?;
+
+        let template = match self.read_compiled_template(&template_path) {
+            Some(template) => template,
+            None => {
+                let template = Template::from_file_with_max_depth(&template_path, self.max_depth)?;
+                self.parse_count.fetch_add(1, Ordering::SeqCst);
+
+                if self.disk_cache {
+                    if let Err(e) = self.write_compiled_template(&template_path, &template) {
+                        warn!("Failed to write compiled template cache: {}", e);
+                    }
+                }
+
+                template
+            }
+        };
 
-        let template = Template::from_file(template_path)?;
+        if self.strict_validation {
+            template.validate()?;
+        }
 
         {
             let mut cache = self.template_cache.write().map_err(|e| {
@@ -102,6 +313,99 @@ impl TemplateEngine {
         Ok(template)
     }
 
+    // Every `.tmpl` file in the search path as (name, path), in search order.
+    fn template_files(&self) -> Result<Vec<(String, PathBuf)>> {
+        let mut files = Vec::new();
+        for dir in self.template_dirs.iter().filter(|dir| dir.exists()) {
+            let mut dir_files: Vec<(String, PathBuf)> = fs::read_dir(dir)?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "tmpl"))
+                .filter_map(|path| Some((path.file_stem()?.to_string_lossy().into_owned(), path)))
+                .collect();
+            dir_files.sort();
+            files.extend(dir_files);
+        }
+        Ok(files)
+    }
+
+    // Templates found in more than one search directory. Only the first path
+    // listed for each name is ever loaded; the rest are shadowed.
+    pub fn find_conflicts(&self) -> Result<Vec<(String, Vec<PathBuf>)>> {
+        let mut paths_by_name: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+        for (name, path) in self.template_files()? {
+            paths_by_name.entry(name).or_default().push(path);
+        }
+
+        Ok(paths_by_name
+            .into_iter()
+            .filter(|(_, paths)| paths.len() > 1)
+            .collect())
+    }
+
+    // A template that fails to load is reported instead of aborting the whole preload.
+    pub fn preload_all(&self) -> Result<PreloadReport> {
+        let names: BTreeSet<String> = self
+            .template_files()?
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+
+        let mut loaded = Vec::new();
+        let mut failed = Vec::new();
+
+        for name in names {
+            match self.load_template(&name) {
+                Ok(_) => loaded.push(name),
+                Err(e) => {
+                    warn!("Skipping template '{}' during preload: {}", name, e);
+                    failed.push((name, e));
+                }
+            }
+        }
+
+        info!(
+            "Preloaded {} templates ({} failed)",
+            loaded.len(),
+            failed.len()
+        );
+        Ok(PreloadReport { loaded, failed })
+    }
+
+    fn compiled_template_path(template_path: &Path) -> PathBuf {
+        template_path.with_extension("tmpl.cache")
+    }
+
+    fn read_compiled_template(&self, template_path: &Path) -> Option<Template> {
+        if !self.disk_cache {
+            return None;
+        }
+
+        let source_modified = fs::metadata(template_path).ok()?.modified().ok()?;
+        let cached = fs::read(Self::compiled_template_path(template_path)).ok()?;
+        let compiled: CompiledTemplate = serde_json::from_slice(&cached).ok()?;
+
+        if compiled.source_modified == source_modified {
+            Some(compiled.template)
+        } else {
+            None
+        }
+    }
+
+    fn write_compiled_template(&self, template_path: &Path, template: &Template) -> Result<()> {
+        let compiled = CompiledTemplate {
+            source_modified: fs::metadata(template_path)?.modified()?,
+            template: template.clone(),
+        };
+
+        fs::write(
+            Self::compiled_template_path(template_path),
+            serde_json::to_vec(&compiled)?,
+        )?;
+
+        Ok(())
+    }
+
     pub fn clear_cache(&self) -> Result<()> {
         let mut cache = self.template_cache.write().map_err(|e| {
             Error::TemplateError(
@@ -121,39 +425,262 @@ impl TemplateEngine {
     ) -> Result<String> {
         let template = self.load_template(template_name)?;
 
+        for variable in template.required_variables() {
+            if !context.variables.contains_key(&variable) {
+                warn!(
+                    "Template '{}' references missing variable '{}'",
+                    template.name, variable
+                );
+            }
+        }
+
         let processed_blocks = self.process_blocks(&template.blocks, context)?;
 
         let template_data = TemplateData {
             blocks: processed_blocks,
             template_name: template.name.clone(),
+            body_classes: context.body_classes.clone(),
+            title: context.title.clone(),
         };
 
-        let rendered_content = renderer.render_template(&template_data)?;
+        let rendered_content = renderer
+            .render_template(&template_data)
+            .map_err(|e| match e {
+                Error::RenderError {
+                    template,
+                    block_kind,
+                    message,
+                } if template.is_empty() => Error::RenderError {
+                    template: template_data.template_name.clone(),
+                    block_kind,
+                    message,
+                },
+                e => e,
+            })?;
 
-        let final_content =
-            self.substitute_variables_in_content(&rendered_content, &context.variables);
+        let final_content = self.substitute_variables_in_content(
+            &rendered_content,
+            &context.variables,
+            renderer.output_format(),
+        );
 
-        Ok(final_content)
+        self.post_process(renderer, final_content)
+    }
+
+    // Renders only the frame declared with `@frame#<block_id>`, e.g. to push a
+    // single panel to a client instead of the whole page.
+    pub fn render_block_by_id<R: Renderer>(
+        &self,
+        template_name: &str,
+        context: &TemplateContext,
+        block_id: &str,
+        renderer: &R,
+    ) -> Result<String> {
+        let template = self.load_template(template_name)?;
+        let block = find_block_by_id(&template.blocks, block_id).ok_or_else(|| {
+            Error::TemplateError(
+                format!(
+                    "Template '{}' has no block with id '{}'",
+                    template.name, block_id
+                )
+                .into(),
+            )
+        })?;
+
+        let processed_blocks = self.process_blocks(std::slice::from_ref(block), context)?;
+        let rendered_content = renderer.render_blocks(&processed_blocks)?;
+        let final_content = self.substitute_variables_in_content(
+            &rendered_content,
+            &context.variables,
+            renderer.output_format(),
+        );
+
+        self.post_process(renderer, final_content)
+    }
+
+    pub fn render_all_formats(
+        &self,
+        template_name: &str,
+        context: &TemplateContext,
+    ) -> Result<HashMap<OutputFormat, String>> {
+        let mut outputs = HashMap::new();
+        outputs.insert(
+            OutputFormat::Html,
+            self.render(template_name, context, &HtmlRenderer::new())?,
+        );
+        outputs.insert(
+            OutputFormat::Text,
+            self.render(template_name, context, &TextRenderer::new())?,
+        );
+        outputs.insert(
+            OutputFormat::Json,
+            self.render(template_name, context, &JsonRenderer::new())?,
+        );
+        Ok(outputs)
+    }
+
+    pub fn validate_render(&self, template_name: &str, context: &TemplateContext) -> Result<()> {
+        self.render(template_name, context, &NullRenderer::new())
+            .map(|_| ())
+    }
+
+    pub fn render_strict<R: Renderer>(
+        &self,
+        template_name: &str,
+        context: &TemplateContext,
+        renderer: &R,
+    ) -> Result<String> {
+        let template = self.load_template(template_name)?;
+        let mut unfilled = Vec::new();
+        collect_directive_args(&template.blocks, "@slot", &mut unfilled);
+        unfilled.retain(|name| !context.blocks.contains_key(name));
+        if !unfilled.is_empty() {
+            return Err(Error::TemplateError(
+                format!(
+                    "Unfilled slots in template '{}': {}",
+                    template_name,
+                    unfilled.join(", ")
+                )
+                .into(),
+            ));
+        }
+
+        let mut missing_paths = Vec::new();
+        collect_directive_args(&template.blocks, "@json", &mut missing_paths);
+        missing_paths.retain(|path| resolve_json_path(&context.data, path).is_none());
+        if !missing_paths.is_empty() {
+            return Err(Error::TemplateError(
+                format!(
+                    "Missing data paths in template '{}': {}",
+                    template_name,
+                    missing_paths.join(", ")
+                )
+                .into(),
+            ));
+        }
+
+        let content = self.render(template_name, context, renderer)?;
+
+        let mut unresolved = find_placeholders(&content);
+        if !unresolved.is_empty() {
+            unresolved.sort();
+            unresolved.dedup();
+            return Err(Error::TemplateError(
+                format!(
+                    "Unresolved variables in template '{}': {}",
+                    template_name,
+                    unresolved.join(", ")
+                )
+                .into(),
+            ));
+        }
+
+        Ok(content)
+    }
+
+    pub fn render_error<R: Renderer>(
+        &self,
+        message: &str,
+        context: &TemplateContext,
+        renderer: &R,
+    ) -> Result<String> {
+        let template_data = TemplateData {
+            blocks: vec![
+                Block::Heading {
+                    level: 1,
+                    text: "Page generation failed".to_string(),
+                },
+                Block::Paragraph(message.to_string()),
+            ],
+            template_name: "error".to_string(),
+            body_classes: context.body_classes.clone(),
+            title: context.title.clone(),
+        };
+
+        let rendered_content = renderer.render_template(&template_data)?;
+
+        self.post_process(
+            renderer,
+            self.substitute_variables_in_content(
+                &rendered_content,
+                &context.variables,
+                renderer.output_format(),
+            ),
+        )
     }
 
     fn process_blocks(&self, blocks: &[Block], context: &TemplateContext) -> Result<Vec<Block>> {
+        self.process_blocks_at(blocks, context, 0)
+    }
+
+    fn process_blocks_at(
+        &self,
+        blocks: &[Block],
+        context: &TemplateContext,
+        depth: usize,
+    ) -> Result<Vec<Block>> {
+        if depth > self.max_depth {
+            return Err(Error::TemplateError(
+                format!(
+                    "Template nesting exceeds the maximum depth of {}",
+                    self.max_depth
+                )
+                .into(),
+            ));
+        }
+
         let mut processed_blocks = Vec::new();
 
         for block in blocks {
             match block {
-                Block::Raw(content) if content.trim() == "@metrics" => {
-                    for metric in &context.metrics {
-                        let trend = metric
-                            .get_label("trend")
-                            .and_then(|t| t.parse::<f64>().ok());
-                        let unit = metric.get_label("unit").map(|s| s.to_string());
-
-                        processed_blocks.push(Block::Metric {
-                            name: metric.name.clone(),
-                            value: metric.value.to_string(),
-                            unit,
-                            trend,
-                        });
+                Block::Raw(content) if is_directive(content, "@metrics") => {
+                    let mut as_table = false;
+                    let mut filters = Vec::new();
+
+                    for arg in directive_args(content) {
+                        if let Some(constraints) = arg.strip_prefix("filter:") {
+                            filters.extend(parse_label_filters(constraints)?);
+                            continue;
+                        }
+
+                        match arg.as_str() {
+                            "table" => as_table = true,
+                            "list" => as_table = false,
+                            _ => {
+                                return Err(Error::TemplateError(
+                                    format!("Unknown @metrics argument '{}'", arg).into(),
+                                ))
+                            }
+                        }
+                    }
+
+                    if context.metrics.is_empty() {
+                        processed_blocks
+                            .push(Block::Paragraph("No metrics available.".to_string()));
+                        continue;
+                    }
+
+                    let metrics: Vec<&Metric> = context
+                        .metrics
+                        .iter()
+                        .filter(|m| filters.iter().all(|(k, v)| m.has_label_value(k, v)))
+                        .collect();
+
+                    if as_table {
+                        processed_blocks.push(self.metrics_table(&metrics));
+                    } else {
+                        for metric in metrics {
+                            let trend = metric.trend();
+                            let unit = metric.unit().map(|s| s.to_string());
+
+                            processed_blocks.push(Block::Metric {
+                                name: metric.name.clone(),
+                                value: metric.value.to_string(),
+                                unit,
+                                trend,
+                                thresholds: MetricThresholds::default(),
+                            });
+                        }
                     }
                 }
 
@@ -181,11 +708,19 @@ impl TemplateEngine {
                             })
                             .collect();
 
-                        processed_blocks.push(Block::Table { headers, rows });
+                        processed_blocks.push(Block::Table {
+                            headers,
+                            rows,
+                            summary: None,
+                        });
                     }
                 }
 
                 Block::Raw(content) if content.trim() == "@traces" => {
+                    if context.traces.is_empty() {
+                        processed_blocks.push(Block::Paragraph("No traces available.".to_string()));
+                    }
+
                     for trace in &context.traces {
                         let status = trace
                             .get_metadata("status")
@@ -195,28 +730,120 @@ impl TemplateEngine {
                         processed_blocks.push(Block::Trace {
                             name: trace.name.clone(),
                             duration_ms: trace.duration_ms,
-                            start_time: trace.start_time.to_rfc3339(),
+                            start_time: trace.start_time,
                             status,
                             metadata: trace.metadata.clone(),
                         });
                     }
                 }
 
+                Block::Raw(content) if is_directive(content, "@waterfall") => {
+                    let root_span_id =
+                        directive_args(content).into_iter().next().ok_or_else(|| {
+                            Error::TemplateError("@waterfall requires a root span id".into())
+                        })?;
+                    processed_blocks.push(self.trace_waterfall(&root_span_id, &context.traces)?);
+                }
+
+                Block::Raw(content) if is_directive(content, "@slot") => {
+                    let name = directive_args(content)
+                        .into_iter()
+                        .next()
+                        .unwrap_or_default();
+                    match context.blocks.get(&name) {
+                        Some(blocks) => processed_blocks.extend(blocks.iter().cloned()),
+                        None => warn!("Slot '{}' was not filled", name),
+                    }
+                }
+
+                Block::Raw(content) if is_directive(content, "@reqvar") => {
+                    let name = directive_args(content)
+                        .into_iter()
+                        .next()
+                        .unwrap_or_default();
+                    let value = context.variables.get(&name).ok_or_else(|| {
+                        Error::TemplateError(
+                            format!("Required variable '{}' is not set", name).into(),
+                        )
+                    })?;
+
+                    processed_blocks.push(Block::Paragraph(value.clone()));
+                }
+
+                Block::Raw(content) if is_directive(content, "@json") => {
+                    let path = directive_args(content)
+                        .into_iter()
+                        .next()
+                        .ok_or_else(|| Error::TemplateError("@json requires a key path".into()))?;
+
+                    // Missing paths keep their directive text rather than a `[[...]]`
+                    // placeholder, so a like-named variable can't fill them in.
+                    let text = match resolve_json_path(&context.data, &path) {
+                        Some(value) => json_scalar_to_string(&path, value)?,
+                        None => {
+                            warn!("@json path '{}' not found in context data", path);
+                            format!("@json{{{}}}", path)
+                        }
+                    };
+
+                    processed_blocks.push(Block::Paragraph(text));
+                }
+
+                Block::Raw(content) if is_directive(content, "@chart") => {
+                    let name = directive_args(content).into_iter().next().ok_or_else(|| {
+                        Error::TemplateError("@chart requires a metric name".into())
+                    })?;
+
+                    let mut points: Vec<(DateTime<Utc>, f64)> = context
+                        .metrics
+                        .iter()
+                        .filter(|m| m.name == name)
+                        .map(|m| (m.timestamp, m.value))
+                        .collect();
+                    points.sort_by_key(|(timestamp, _)| *timestamp);
+
+                    processed_blocks.push(Block::Chart { name, points });
+                }
+
                 Block::Container(nested_blocks) => {
-                    let processed_nested = self.process_blocks(nested_blocks, context)?;
+                    let processed_nested =
+                        self.process_blocks_at(nested_blocks, context, depth + 1)?;
                     processed_blocks.push(Block::Container(processed_nested));
                 }
 
-                Block::Frame { title, content } => {
-                    let processed_content = self.process_blocks(content, context)?;
+                Block::Empty {
+                    collection,
+                    content,
+                } => {
+                    let is_empty = match collection.as_str() {
+                        "metrics" => context.metrics.is_empty(),
+                        "logs" => context.logs.is_empty(),
+                        "traces" => context.traces.is_empty(),
+                        other => {
+                            return Err(Error::TemplateError(
+                                format!("Unknown collection '{}' in @empty", other).into(),
+                            ))
+                        }
+                    };
+                    if is_empty {
+                        let processed_content =
+                            self.process_blocks_at(content, context, depth + 1)?;
+                        processed_blocks.push(Block::Container(processed_content));
+                    }
+                }
+
+                Block::Frame { id, title, content } => {
+                    let processed_content = self.process_blocks_at(content, context, depth + 1)?;
                     processed_blocks.push(Block::Frame {
+                        id: id.clone(),
                         title: title.clone(),
                         content: processed_content,
                     });
                 }
 
                 Block::Output(nested_blocks) => {
-                    let processed_nested = self.process_blocks(nested_blocks, context)?;
+                    let processed_nested =
+                        self.process_blocks_at(nested_blocks, context, depth + 1)?;
                     processed_blocks.push(Block::Output(processed_nested));
                 }
 
@@ -227,16 +854,95 @@ impl TemplateEngine {
         Ok(processed_blocks)
     }
 
+    fn metrics_table(&self, metrics: &[&Metric]) -> Block {
+        let headers = vec![
+            "Name".to_string(),
+            "Value".to_string(),
+            "Unit".to_string(),
+            "Trend".to_string(),
+        ];
+
+        let rows = metrics
+            .iter()
+            .map(|metric| {
+                vec![
+                    metric.name.clone(),
+                    metric.value.to_string(),
+                    metric.unit().unwrap_or_default().to_string(),
+                    metric.get_label("trend").cloned().unwrap_or_default(),
+                ]
+            })
+            .collect();
+
+        Block::Table {
+            headers,
+            rows,
+            summary: metrics_summary(metrics),
+        }
+    }
+
+    fn trace_waterfall(&self, root_span_id: &str, traces: &[Trace]) -> Result<Block> {
+        let root = traces
+            .iter()
+            .find(|t| t.span_id == root_span_id)
+            .ok_or_else(|| {
+                Error::TemplateError(
+                    format!("Unknown span '{}' in @waterfall", root_span_id).into(),
+                )
+            })?;
+
+        let mut spans = Vec::new();
+        let mut stack = vec![(root, 0)];
+
+        while let Some((trace, depth)) = stack.pop() {
+            spans.push(WaterfallSpan {
+                name: trace.name.clone(),
+                depth,
+                offset_ms: (trace.start_time - root.start_time)
+                    .num_milliseconds()
+                    .max(0) as u64,
+                duration_ms: trace.duration_ms,
+            });
+
+            if depth >= traces.len() {
+                continue;
+            }
+
+            let mut children: Vec<&Trace> = traces
+                .iter()
+                .filter(|t| t.parent_id.as_deref() == Some(trace.span_id.as_str()))
+                .collect();
+            children.sort_by_key(|t| std::cmp::Reverse(t.start_time));
+            stack.extend(children.into_iter().map(|child| (child, depth + 1)));
+        }
+
+        let total_ms = spans
+            .iter()
+            .map(|span| span.offset_ms + span.duration_ms)
+            .max()
+            .unwrap_or(0);
+
+        Ok(Block::Waterfall { total_ms, spans })
+    }
+
     fn substitute_variables_in_content(
         &self,
         content: &str,
         variables: &HashMap<String, String>,
+        format: Option<OutputFormat>,
     ) -> String {
         let mut result = content.to_string();
 
         for (name, value) in variables {
             let pattern = format!("[[{}]]", name);
-            result = result.replace(&pattern, value);
+            // Placeholders in JSON output sit inside string literals, so the
+            // value has to be escaped to keep the document valid.
+            if format == Some(OutputFormat::Json) {
+                let escaped = serde_json::Value::String(value.clone()).to_string();
+                result = result.replace(&pattern, &escaped[1..escaped.len() - 1]);
+            } else {
+                result = result.replace(&pattern, value);
+            }
         }
 
         result
@@ -260,18 +966,165 @@ impl TemplateEngine {
         let html_path = output_dir.join(format!("{}.html", base_name));
         let text_path = output_dir.join(format!("{}.txt", base_name));
 
-        fs::write(&html_path, html_content).map_err(|e| {
+        write_if_changed(&html_path, html_content.as_bytes()).map_err(|e| {
             Error::TemplateError(format!("Failed to write HTML output: {}", e).into())
         })?;
 
-        fs::write(&text_path, text_content).map_err(|e| {
+        write_if_changed(&text_path, text_content.as_bytes()).map_err(|e| {
             Error::TemplateError(format!("Failed to write text output: {}", e).into())
         })?;
 
+        if self.gzip_output {
+            write_gzip(&html_path, html_content)?;
+            write_gzip(&text_path, text_content)?;
+        }
+
         Ok(())
     }
 }
 
+fn write_if_changed(path: &Path, content: &[u8]) -> std::io::Result<bool> {
+    if fs::read(path).is_ok_and(|existing| existing == content) {
+        info!("{} unchanged, skipping write", path.display());
+        return Ok(false);
+    }
+
+    fs::write(path, content)?;
+    Ok(true)
+}
+
+fn write_gzip(path: &Path, content: &str) -> Result<()> {
+    let mut gz_path = path.as_os_str().to_owned();
+    gz_path.push(".gz");
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(content.as_bytes())
+        .and_then(|_| encoder.finish())
+        .and_then(|compressed| write_if_changed(Path::new(&gz_path), &compressed))
+        .map(|_| ())
+        .map_err(|e| Error::TemplateError(format!("Failed to write gzip output: {}", e).into()))
+}
+
+fn is_directive(content: &str, directive: &str) -> bool {
+    let content = content.trim();
+    content == directive
+        || content
+            .strip_prefix(directive)
+            .is_some_and(|rest| rest.starts_with('{'))
+}
+
+fn parse_label_filters(constraints: &str) -> Result<Vec<(String, String)>> {
+    constraints
+        .split(',')
+        .map(|constraint| match constraint.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(Error::TemplateError(
+                format!("Invalid label filter '{}'", constraint.trim()).into(),
+            )),
+        })
+        .collect()
+}
+
+// First argument of every `directive` in the block tree, e.g. slot names.
+fn collect_directive_args(blocks: &[Block], directive: &str, args: &mut Vec<String>) {
+    for block in blocks {
+        match block {
+            Block::Raw(content) if is_directive(content, directive) => {
+                args.push(
+                    directive_args(content)
+                        .into_iter()
+                        .next()
+                        .unwrap_or_default(),
+                );
+            }
+            Block::Output(nested) | Block::Container(nested) => {
+                collect_directive_args(nested, directive, args)
+            }
+            Block::Frame { content, .. } | Block::Empty { content, .. } => {
+                collect_directive_args(content, directive, args)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn find_block_by_id<'a>(blocks: &'a [Block], block_id: &str) -> Option<&'a Block> {
+    blocks.iter().find_map(|block| match block {
+        Block::Frame { id, .. } if id.as_deref() == Some(block_id) => Some(block),
+        Block::Frame { content, .. }
+        | Block::Output(content)
+        | Block::Container(content)
+        | Block::Empty { content, .. } => find_block_by_id(content, block_id),
+        _ => None,
+    })
+}
+
+fn resolve_json_path<'a>(
+    data: &'a HashMap<String, serde_json::Value>,
+    path: &str,
+) -> Option<&'a serde_json::Value> {
+    let mut segments = path.split('.');
+    let mut value = data.get(segments.next()?)?;
+
+    for segment in segments {
+        value = match value {
+            serde_json::Value::Object(map) => map.get(segment)?,
+            serde_json::Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+
+    Some(value)
+}
+
+fn json_scalar_to_string(path: &str, value: &serde_json::Value) -> Result<String> {
+    match value {
+        serde_json::Value::String(s) => Ok(s.clone()),
+        serde_json::Value::Null => Ok(String::new()),
+        serde_json::Value::Bool(_) | serde_json::Value::Number(_) => Ok(value.to_string()),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => Err(Error::TemplateError(
+            format!("@json path '{}' does not resolve to a scalar value", path).into(),
+        )),
+    }
+}
+
+fn directive_args(content: &str) -> Vec<String> {
+    content
+        .trim()
+        .split('{')
+        .skip(1)
+        .map(|arg| arg.trim_end_matches('}').trim().to_string())
+        .collect()
+}
+
+// Summary row for metric tables: count plus min/mean/max over the finite
+// values. Renderers decide whether to show it.
+fn metrics_summary(metrics: &[&Metric]) -> Option<Vec<String>> {
+    let values: Vec<f64> = metrics
+        .iter()
+        .map(|metric| metric.value)
+        .filter(|value| value.is_finite())
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+
+    let format = |value: f64| format_metric_value(&format!("{:.6}", value), None);
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    Some(vec![
+        format!("Count: {}", values.len()),
+        format!("Mean: {}", format(mean)),
+        format!("Min: {}", format(min)),
+        format!("Max: {}", format(max)),
+    ])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,7 +1134,7 @@ mod tests {
     use chrono::Utc;
     use serial_test::serial;
     use std::fs;
-    use std::io::Write;
+    use std::io::{Read, Write};
     use tempfile::{tempdir, NamedTempFile};
 
     fn create_test_template() -> (NamedTempFile, String) {
@@ -328,12 +1181,14 @@ mod tests {
                 value: 78.5,
                 timestamp: now,
                 labels: cpu_labels,
+                unit: None,
             },
             Metric {
                 name: "Memory".to_string(),
                 value: 4.2,
                 timestamp: now,
                 labels: mem_labels,
+                unit: None,
             },
         ];
 
@@ -510,4 +1365,932 @@ mod tests {
         assert_ne!(template1.content, template4.content);
         assert!(template4.content.contains("Modified Template"));
     }
+
+    #[test]
+    fn test_disk_cache_skips_parser() {
+        let template_dir = tempdir().unwrap();
+        let template_path = template_dir.path().join("cached.tmpl");
+        fs::write(&template_path, "@heading{1}{Cached Template}").unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path()).with_disk_cache(true);
+        let template = engine.load_template("cached").unwrap();
+        assert_eq!(engine.parse_count(), 1);
+        assert!(template_dir.path().join("cached.tmpl.cache").exists());
+
+        let warm_engine = TemplateEngine::new(template_dir.path()).with_disk_cache(true);
+        let cached = warm_engine.load_template("cached").unwrap();
+        assert_eq!(warm_engine.parse_count(), 0);
+        assert_eq!(cached.blocks, template.blocks);
+
+        fs::write(&template_path, "@heading{1}{Modified Template}").unwrap();
+        let file = fs::File::options()
+            .write(true)
+            .open(&template_path)
+            .unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+
+        let stale_engine = TemplateEngine::new(template_dir.path()).with_disk_cache(true);
+        let reparsed = stale_engine.load_template("cached").unwrap();
+        assert_eq!(stale_engine.parse_count(), 1);
+        assert!(reparsed.content.contains("Modified Template"));
+    }
+
+    #[test]
+    fn test_write_output_gzip() {
+        let template_dir = tempdir().unwrap();
+        let output_dir = tempdir().unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path()).with_gzip_output(true);
+        engine
+            .write_output("<h1>Home</h1>", "Home", output_dir.path(), "index")
+            .unwrap();
+
+        let html = fs::read(output_dir.path().join("index.html")).unwrap();
+        let compressed = fs::read(output_dir.path().join("index.html.gz")).unwrap();
+        let mut decompressed = Vec::new();
+        flate2::read::GzDecoder::new(&compressed[..])
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, html);
+        assert!(output_dir.path().join("index.txt.gz").exists());
+
+        let plain_engine = TemplateEngine::new(template_dir.path());
+        plain_engine
+            .write_output("<h1>About</h1>", "About", output_dir.path(), "about")
+            .unwrap();
+        assert!(!output_dir.path().join("about.html.gz").exists());
+    }
+
+    #[test]
+    fn test_write_output_skips_unchanged_content() {
+        let template_dir = tempdir().unwrap();
+        let output_dir = tempdir().unwrap();
+        let html_path = output_dir.path().join("index.html");
+
+        let engine = TemplateEngine::new(template_dir.path()).with_gzip_output(true);
+        engine
+            .write_output("<h1>Home</h1>", "Home", output_dir.path(), "index")
+            .unwrap();
+
+        let old_time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        for name in ["index.html", "index.txt", "index.html.gz"] {
+            fs::File::options()
+                .write(true)
+                .open(output_dir.path().join(name))
+                .unwrap()
+                .set_modified(old_time)
+                .unwrap();
+        }
+
+        engine
+            .write_output("<h1>Home</h1>", "Home", output_dir.path(), "index")
+            .unwrap();
+
+        let modified = |name: &str| {
+            fs::metadata(output_dir.path().join(name))
+                .unwrap()
+                .modified()
+                .unwrap()
+        };
+        assert_eq!(modified("index.html"), old_time);
+        assert_eq!(modified("index.txt"), old_time);
+        assert_eq!(modified("index.html.gz"), old_time);
+
+        engine
+            .write_output("<h1>Updated</h1>", "Home", output_dir.path(), "index")
+            .unwrap();
+        assert_ne!(modified("index.html"), old_time);
+        assert_eq!(modified("index.txt"), old_time);
+        assert_eq!(fs::read_to_string(&html_path).unwrap(), "<h1>Updated</h1>");
+    }
+
+    #[test]
+    fn test_render_strict_unresolved_variables() {
+        let template_dir = tempdir().unwrap();
+        fs::write(
+            template_dir.path().join("strict.tmpl"),
+            "@paragraph{Host: [[hostname]]}\n@var{undefined_var}",
+        )
+        .unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path());
+        let renderer = TextRenderer::new();
+        let context = TemplateContext::new().with_variable("hostname", "server-1");
+
+        let lenient = engine.render("strict", &context, &renderer).unwrap();
+        assert!(lenient.contains("server-1"));
+        assert!(lenient.contains("[[undefined_var]]"));
+
+        let strict = engine.render_strict("strict", &context, &renderer);
+        let err = strict.unwrap_err().to_string();
+        assert!(err.contains("undefined_var"));
+        assert!(!err.contains("hostname"));
+
+        let complete = context.with_variable("undefined_var", "defined");
+        let rendered = engine
+            .render_strict("strict", &complete, &renderer)
+            .unwrap();
+        assert!(rendered.contains("defined"));
+    }
+
+    #[test]
+    fn test_metrics_table_directive() {
+        let template_dir = tempdir().unwrap();
+        fs::write(
+            template_dir.path().join("metrics_table.tmpl"),
+            "@frame{Table}{\n@metrics{table}\n}\n@frame{List}{\n@metrics{list}\n}",
+        )
+        .unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path());
+        let context = create_test_context();
+
+        let template = engine.load_template("metrics_table").unwrap();
+        let processed_blocks = engine.process_blocks(&template.blocks, &context).unwrap();
+
+        match &processed_blocks[0] {
+            Block::Frame { content, .. } => match &content[0] {
+                Block::Table { headers, rows, .. } => {
+                    assert_eq!(headers, &["Name", "Value", "Unit", "Trend"]);
+                    assert_eq!(rows.len(), 2);
+                    assert_eq!(rows[0], ["CPU Usage", "78.5", "%", "+2.3"]);
+                }
+                _ => panic!("Expected table block"),
+            },
+            _ => panic!("Expected frame block"),
+        }
+
+        match &processed_blocks[1] {
+            Block::Frame { content, .. } => {
+                assert_eq!(content.len(), 2);
+                assert!(content.iter().all(|b| matches!(b, Block::Metric { .. })));
+            }
+            _ => panic!("Expected frame block"),
+        }
+
+        let html = engine
+            .render("metrics_table", &context, &HtmlRenderer::new())
+            .unwrap();
+        assert!(html.contains("<th>Name</th><th>Value</th><th>Unit</th><th>Trend</th>"));
+
+        fs::write(
+            template_dir.path().join("metrics_bogus.tmpl"),
+            "@metrics{bogus}",
+        )
+        .unwrap();
+        assert!(engine
+            .render("metrics_bogus", &context, &HtmlRenderer::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_metrics_table_summary_row() {
+        let template_dir = tempdir().unwrap();
+        fs::write(template_dir.path().join("summary.tmpl"), "@metrics{table}").unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path());
+        let context = TemplateContext::new().with_metrics(vec![
+            Metric::new("Requests", 1.0),
+            Metric::new("Errors", 2.0),
+            Metric::new("Retries", 4.0),
+        ]);
+
+        let html = engine
+            .render(
+                "summary",
+                &context,
+                &HtmlRenderer::new().with_metrics_summary_row(true),
+            )
+            .unwrap();
+        let footer = &html[html.find("<tfoot>").unwrap()..html.find("</tfoot>").unwrap()];
+        assert!(footer.contains("<td>Count: 3</td>"));
+        assert!(footer.contains("<td>Mean: 2.333333</td>"));
+
+        let text = engine
+            .render(
+                "summary",
+                &context,
+                &TextRenderer::new().with_metrics_summary_row(true),
+            )
+            .unwrap();
+        assert!(text.contains("Mean: 2.333333"));
+
+        let plain = engine
+            .render("summary", &context, &HtmlRenderer::new())
+            .unwrap();
+        assert!(!plain.contains("<tfoot>"));
+    }
+
+    #[test]
+    fn test_render_block_by_id() {
+        let template_dir = tempdir().unwrap();
+        fs::write(
+            template_dir.path().join("dashboard.tmpl"),
+            "@heading{1}{Dashboard}\n\
+             @frame#system{System}{\n@metrics\n}\n\
+             @frame#events{Events}{\n@logs\n}",
+        )
+        .unwrap();
+        let engine = TemplateEngine::new(template_dir.path());
+        let context = create_test_context();
+
+        let html = engine
+            .render_block_by_id("dashboard", &context, "system", &HtmlRenderer::new())
+            .unwrap();
+        assert!(html.contains("System"));
+        assert!(html.contains("CPU Usage"));
+        assert!(!html.contains("Dashboard"));
+        assert!(!html.contains("Events"));
+        assert!(!html.contains("Server started"));
+        assert!(!html.contains("<!DOCTYPE"));
+
+        let err = engine
+            .render_block_by_id("dashboard", &context, "missing", &HtmlRenderer::new())
+            .unwrap_err();
+        assert!(err.to_string().contains("no block with id 'missing'"));
+    }
+
+    #[test]
+    fn test_empty_directive() {
+        let template_dir = tempdir().unwrap();
+        fs::write(
+            template_dir.path().join("fresh.tmpl"),
+            "@heading{1}{Dashboard}\n@empty{metrics}{\n@paragraph{No metrics yet}\n}",
+        )
+        .unwrap();
+        let engine = TemplateEngine::new(template_dir.path());
+
+        let html = engine
+            .render("fresh", &TemplateContext::new(), &HtmlRenderer::new())
+            .unwrap();
+        assert!(html.contains("No metrics yet"));
+
+        let html = engine
+            .render("fresh", &create_test_context(), &HtmlRenderer::new())
+            .unwrap();
+        assert!(!html.contains("No metrics yet"));
+
+        fs::write(
+            template_dir.path().join("bogus.tmpl"),
+            "@empty{widgets}{\n@paragraph{Nothing}\n}",
+        )
+        .unwrap();
+        assert!(engine.load_template("bogus").is_err());
+    }
+
+    #[test]
+    fn test_empty_collections_render_placeholder() {
+        let template_dir = tempdir().unwrap();
+        fs::write(
+            template_dir.path().join("empty.tmpl"),
+            "@frame{Metrics}{\n@metrics\n}\n@frame{Traces}{\n@traces\n}\n@frame{Logs}{\n@logs\n}",
+        )
+        .unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path());
+        let context = TemplateContext::new();
+
+        let html = engine
+            .render("empty", &context, &HtmlRenderer::new())
+            .unwrap();
+        let text = engine
+            .render("empty", &context, &TextRenderer::new())
+            .unwrap();
+
+        for output in [&html, &text] {
+            assert!(output.contains("No metrics available."));
+            assert!(output.contains("No traces available."));
+            assert!(output.contains("No logs available."));
+        }
+    }
+
+    #[test]
+    fn test_metrics_label_filter_directive() {
+        let template_dir = tempdir().unwrap();
+        fs::write(
+            template_dir.path().join("filtered.tmpl"),
+            "@frame{System}{\n@metrics{filter:group=system}\n}\n\
+             @frame{App}{\n@metrics{table}{filter:group=app, env=prod}\n}\n\
+             @frame{None}{\n@metrics{filter:missing=label}\n}",
+        )
+        .unwrap();
+
+        let metrics = vec![
+            Metric::new("CPU Usage", 78.5).with_label("group", "system"),
+            Metric::new("Memory", 4.2).with_label("group", "system"),
+            Metric::new("Requests", 120.0)
+                .with_label("group", "app")
+                .with_label("env", "prod"),
+            Metric::new("Staging Requests", 12.0)
+                .with_label("group", "app")
+                .with_label("env", "staging"),
+        ];
+        let context = TemplateContext::new().with_metrics(metrics);
+
+        let engine = TemplateEngine::new(template_dir.path());
+        let template = engine.load_template("filtered").unwrap();
+        let processed_blocks = engine.process_blocks(&template.blocks, &context).unwrap();
+
+        let frame_content = |index: usize| match &processed_blocks[index] {
+            Block::Frame { content, .. } => content.clone(),
+            _ => panic!("Expected frame block"),
+        };
+
+        let system: Vec<String> = frame_content(0)
+            .iter()
+            .map(|b| match b {
+                Block::Metric { name, .. } => name.clone(),
+                _ => panic!("Expected metric block"),
+            })
+            .collect();
+        assert_eq!(system, vec!["CPU Usage", "Memory"]);
+
+        match &frame_content(1)[0] {
+            Block::Table { rows, .. } => {
+                assert_eq!(rows.len(), 1);
+                assert_eq!(rows[0][0], "Requests");
+            }
+            _ => panic!("Expected table block"),
+        }
+
+        assert!(frame_content(2).is_empty());
+
+        fs::write(
+            template_dir.path().join("bad_filter.tmpl"),
+            "@metrics{filter:group}",
+        )
+        .unwrap();
+        assert!(engine
+            .render("bad_filter", &context, &TextRenderer::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_waterfall_directive() {
+        let template_dir = tempdir().unwrap();
+        let start = Utc::now();
+
+        let root = Trace::with_times(
+            "GET /api/users",
+            start,
+            start + chrono::Duration::milliseconds(100),
+        );
+        let auth = Trace::with_times(
+            "auth",
+            start + chrono::Duration::milliseconds(5),
+            start + chrono::Duration::milliseconds(25),
+        )
+        .with_parent(&root.span_id);
+        let query = Trace::with_times(
+            "db query",
+            start + chrono::Duration::milliseconds(30),
+            start + chrono::Duration::milliseconds(90),
+        )
+        .with_parent(&root.span_id);
+        let cache = Trace::with_times(
+            "cache hit",
+            start + chrono::Duration::milliseconds(95),
+            start + chrono::Duration::milliseconds(95),
+        )
+        .with_parent(&query.span_id);
+        let unrelated =
+            Trace::with_times("other", start, start + chrono::Duration::milliseconds(10));
+
+        fs::write(
+            template_dir.path().join("waterfall.tmpl"),
+            format!("@waterfall{{{}}}", root.span_id),
+        )
+        .unwrap();
+
+        let context = TemplateContext::new().with_traces(vec![
+            query.clone(),
+            unrelated,
+            cache,
+            root.clone(),
+            auth.clone(),
+        ]);
+        let engine = TemplateEngine::new(template_dir.path());
+
+        let template = engine.load_template("waterfall").unwrap();
+        let processed_blocks = engine.process_blocks(&template.blocks, &context).unwrap();
+        match &processed_blocks[0] {
+            Block::Waterfall { total_ms, spans } => {
+                assert_eq!(*total_ms, 100);
+                let rows: Vec<(&str, usize, u64)> = spans
+                    .iter()
+                    .map(|s| (s.name.as_str(), s.depth, s.offset_ms))
+                    .collect();
+                assert_eq!(
+                    rows,
+                    vec![
+                        ("GET /api/users", 0, 0),
+                        ("auth", 1, 5),
+                        ("db query", 1, 30),
+                        ("cache hit", 2, 95),
+                    ]
+                );
+            }
+            _ => panic!("Expected waterfall block"),
+        }
+
+        let text = engine
+            .render("waterfall", &context, &TextRenderer::new().with_width(60))
+            .unwrap();
+        let lines: Vec<&str> = text.lines().filter(|l| l.contains('│')).collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("GET /api/users"));
+        assert!(lines[1].starts_with("  auth"));
+        assert!(lines[2].starts_with("  db query"));
+        assert!(lines[3].starts_with("    cache hit"));
+        assert!(
+            lines[3].contains('█'),
+            "Zero-duration span should still be visible"
+        );
+
+        let html = engine
+            .render("waterfall", &context, &HtmlRenderer::new())
+            .unwrap();
+        assert_eq!(html.matches("class=\"terminal-waterfall-row\"").count(), 4);
+        assert!(html.contains("margin-left: 30.00%; width: 60.00%"));
+        assert!(html.contains("margin-left: 95.00%; width: 0.00%"));
+
+        fs::write(template_dir.path().join("missing.tmpl"), "@waterfall{nope}").unwrap();
+        assert!(engine
+            .render("missing", &context, &TextRenderer::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_chart_directive() {
+        let template_dir = tempdir().unwrap();
+        fs::write(template_dir.path().join("chart.tmpl"), "@chart{cpu}").unwrap();
+
+        let start = Utc::now();
+        let context = TemplateContext::new().with_metrics(vec![
+            Metric::with_timestamp("cpu", 30.0, start + chrono::Duration::seconds(2)),
+            Metric::with_timestamp("memory", 4.0, start),
+            Metric::with_timestamp("cpu", 10.0, start),
+            Metric::with_timestamp("cpu", 20.0, start + chrono::Duration::seconds(1)),
+        ]);
+        let engine = TemplateEngine::new(template_dir.path());
+
+        let template = engine.load_template("chart").unwrap();
+        let processed_blocks = engine.process_blocks(&template.blocks, &context).unwrap();
+        match &processed_blocks[0] {
+            Block::Chart { name, points } => {
+                assert_eq!(name, "cpu");
+                let values: Vec<f64> = points.iter().map(|(_, v)| *v).collect();
+                assert_eq!(values, vec![10.0, 20.0, 30.0]);
+            }
+            _ => panic!("Expected chart block"),
+        }
+
+        let html = engine
+            .render("chart", &context, &HtmlRenderer::new())
+            .unwrap();
+        assert!(html.contains("<polyline"));
+
+        fs::write(template_dir.path().join("bad_chart.tmpl"), "@chart").unwrap();
+        assert!(engine
+            .render("bad_chart", &context, &HtmlRenderer::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_render_all_formats() {
+        let template_dir = tempdir().unwrap();
+        fs::write(
+            template_dir.path().join("simple.tmpl"),
+            "@heading{1}{Hello [[name]]}",
+        )
+        .unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path());
+        let context = TemplateContext::new().with_variable("name", "World");
+
+        let outputs = engine.render_all_formats("simple", &context).unwrap();
+        assert_eq!(outputs.len(), 3);
+        assert!(outputs[&OutputFormat::Html].contains("Hello World"));
+        assert!(outputs[&OutputFormat::Text].contains("Hello World"));
+        assert!(!outputs[&OutputFormat::Json].is_empty());
+
+        assert!(engine.render_all_formats("missing", &context).is_err());
+    }
+
+    #[test]
+    fn test_render_json_escapes_variables() {
+        let template_dir = tempdir().unwrap();
+        fs::write(
+            template_dir.path().join("simple.tmpl"),
+            "@heading{1}{Hello [[name]]}",
+        )
+        .unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path());
+        let context = TemplateContext::new().with_variable("name", "\"quoted\" \\ line\nbreak");
+
+        let output = engine
+            .render("simple", &context, &JsonRenderer::new())
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(parsed
+            .to_string()
+            .contains("Hello \\\"quoted\\\" \\\\ line\\nbreak"));
+
+        let outputs = engine.render_all_formats("simple", &context).unwrap();
+        assert!(serde_json::from_str::<serde_json::Value>(&outputs[&OutputFormat::Json]).is_ok());
+    }
+
+    #[test]
+    fn test_json_directive() {
+        let template_dir = tempdir().unwrap();
+        fs::write(
+            template_dir.path().join("profile.tmpl"),
+            "@json{user.name}\n@json{user.roles.1}",
+        )
+        .unwrap();
+        fs::write(
+            template_dir.path().join("missing.tmpl"),
+            "@json{user.email}",
+        )
+        .unwrap();
+        fs::write(template_dir.path().join("object.tmpl"), "@json{user}").unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path());
+        let context = TemplateContext::new().with_data(
+            "user",
+            serde_json::json!({ "name": "Max", "roles": ["admin", "ops"] }),
+        );
+
+        let template = engine.load_template("profile").unwrap();
+        let processed_blocks = engine.process_blocks(&template.blocks, &context).unwrap();
+        assert_eq!(
+            processed_blocks,
+            vec![
+                Block::Paragraph("Max".to_string()),
+                Block::Paragraph("ops".to_string()),
+            ]
+        );
+
+        let text = engine
+            .render("profile", &context, &TextRenderer::new())
+            .unwrap();
+        assert!(text.contains("Max"));
+
+        assert!(engine
+            .render("missing", &context, &TextRenderer::new())
+            .is_ok());
+        assert!(engine
+            .render_strict("missing", &context, &TextRenderer::new())
+            .is_err());
+        assert!(engine
+            .render("object", &context, &TextRenderer::new())
+            .is_err());
+
+        // A plain variable sharing the path's name must not hide the missing data.
+        let shadowed = context.with_variable("user.email", "max@example.com");
+        let text = engine
+            .render("missing", &shadowed, &TextRenderer::new())
+            .unwrap();
+        assert!(text.contains("@json{user.email}"));
+        assert!(!text.contains("max@example.com"));
+        let err = engine
+            .render_strict("missing", &shadowed, &TextRenderer::new())
+            .unwrap_err();
+        assert!(err.to_string().contains("user.email"));
+    }
+
+    #[test]
+    fn test_line_ending() {
+        assert_eq!(LineEnding::Lf.apply("a\nb\n".to_string()), "a\nb\n");
+        assert_eq!(
+            LineEnding::CrLf.apply("a\nb\r\nc\n".to_string()),
+            "a\r\nb\r\nc\r\n"
+        );
+
+        let template_dir = tempdir().unwrap();
+        let context = TemplateContext::new();
+        let lf = TemplateEngine::new(template_dir.path());
+        lf.register_template("page", "@heading{1}{Hello}").unwrap();
+        let text = lf.render("page", &context, &TextRenderer::new()).unwrap();
+        assert!(text.contains('\n'));
+        assert!(!text.contains('\r'));
+
+        let crlf = TemplateEngine::new(template_dir.path()).with_line_ending(LineEnding::CrLf);
+        crlf.register_template("page", "@heading{1}{Hello}")
+            .unwrap();
+        let text = crlf.render("page", &context, &TextRenderer::new()).unwrap();
+        assert_eq!(text.matches('\n').count(), text.matches("\r\n").count());
+    }
+
+    #[test]
+    fn test_post_processors_run_in_order() {
+        let template_dir = tempdir().unwrap();
+        let engine = TemplateEngine::new(template_dir.path())
+            .with_post_processor(Box::new(|format, content| {
+                Ok(match format {
+                    OutputFormat::Html => format!("{}<!-- analytics -->", content),
+                    _ => content,
+                })
+            }))
+            .with_post_processor(Box::new(|_, content| {
+                Ok(format!("{}<!-- done -->", content))
+            }));
+        engine
+            .register_template("page", "@heading{1}{Hello}")
+            .unwrap();
+        let context = TemplateContext::new();
+
+        let html = engine
+            .render("page", &context, &HtmlRenderer::new())
+            .unwrap();
+        assert!(html.ends_with("<!-- analytics --><!-- done -->"));
+
+        let text = engine
+            .render("page", &context, &TextRenderer::new())
+            .unwrap();
+        assert!(!text.contains("<!-- analytics -->"));
+        assert!(text.ends_with("<!-- done -->"));
+
+        let null = engine
+            .render("page", &context, &NullRenderer::new())
+            .unwrap();
+        assert!(null.is_empty());
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let template_dir = tempdir().unwrap();
+        let engine = TemplateEngine::new(template_dir.path()).with_max_depth(2);
+
+        assert!(engine
+            .register_template("deep", "@output{@output{@output{@paragraph{x}}}}")
+            .is_err());
+
+        // Blocks that never went through the parser are checked while expanding.
+        let mut nested = vec![Block::Paragraph("x".to_string())];
+        for _ in 0..3 {
+            nested = vec![Block::Frame {
+                id: None,
+                title: None,
+                content: nested,
+            }];
+        }
+        let err = engine
+            .process_blocks(&nested, &TemplateContext::new())
+            .unwrap_err();
+        assert!(err.to_string().contains("maximum depth of 2"));
+    }
+
+    #[test]
+    fn test_register_template() {
+        let template_dir = tempdir().unwrap();
+        let engine = TemplateEngine::new(template_dir.path());
+
+        engine
+            .register_template("inline", "@heading{1}{Hello [[name]]}")
+            .unwrap();
+        let context = TemplateContext::new().with_variable("name", "World");
+
+        let text = engine
+            .render("inline", &context, &TextRenderer::new())
+            .unwrap();
+        assert!(text.contains("Hello World"));
+
+        engine.clear_cache().unwrap();
+        assert!(engine.load_template("inline").is_ok());
+        assert_eq!(fs::read_dir(template_dir.path()).unwrap().count(), 0);
+
+        assert!(engine.register_template("broken", "@frame{Oops}{").is_err());
+    }
+
+    #[test]
+    fn test_strict_validation_rejects_mismatched_rows() {
+        let template_dir = tempdir().unwrap();
+        let table = "@table{\n@headers{Name|Value}\n@row{CPU}\n}";
+
+        let lenient = TemplateEngine::new(template_dir.path());
+        assert!(lenient.register_template("table", table).is_ok());
+
+        let strict = TemplateEngine::new(template_dir.path()).with_strict_validation(true);
+        assert!(strict.register_template("table", table).is_err());
+        assert!(strict
+            .register_template("table", "@table{\n@headers{Name|Value}\n@row{CPU|42}\n}")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_validate_render() {
+        let template_dir = tempdir().unwrap();
+        fs::write(
+            template_dir.path().join("valid.tmpl"),
+            "@heading{1}{Status}\n@frame{Traces}{\n  @trace{API Request}{157}{2025-03-15T12:34:56Z}{200}\n}\n@metrics",
+        )
+        .unwrap();
+        fs::write(
+            template_dir.path().join("invalid.tmpl"),
+            "@frame{Traces}{\n  @trace{API Request}{fast}{2025-03-15T12:34:56Z}{200}\n}",
+        )
+        .unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path());
+        let context = TemplateContext::new().with_metrics(vec![Metric::new("cpu", 1.0)]);
+
+        assert!(engine.validate_render("valid", &context).is_ok());
+        assert!(engine.validate_render("invalid", &context).is_err());
+
+        // These parse fine and only fail once the nested blocks are expanded.
+        fs::write(
+            template_dir.path().join("missing_var.tmpl"),
+            "@frame{Host}{\n  @reqvar{hostname}\n}",
+        )
+        .unwrap();
+        fs::write(
+            template_dir.path().join("object.tmpl"),
+            "@frame{User}{\n  @json{user}\n}",
+        )
+        .unwrap();
+        assert!(engine.load_template("missing_var").is_ok());
+        assert!(engine.load_template("object").is_ok());
+
+        let context = context.with_data("user", serde_json::json!({ "name": "Max" }));
+        assert!(engine.validate_render("missing_var", &context).is_err());
+        assert!(engine.validate_render("object", &context).is_err());
+        assert!(engine
+            .validate_render("missing_var", &context.with_variable("hostname", "web-1"))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_preload_all_reports_broken_templates() {
+        let template_dir = tempdir().unwrap();
+        fs::write(template_dir.path().join("home.tmpl"), "@heading{1}{Home}").unwrap();
+        fs::write(template_dir.path().join("broken.tmpl"), "@frame{Oops}{").unwrap();
+        fs::write(template_dir.path().join("notes.txt"), "not a template").unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path());
+        let report = engine.preload_all().unwrap();
+
+        assert_eq!(report.loaded, vec!["home"]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, "broken");
+        assert_eq!(engine.parse_count(), 1);
+        assert!(engine.load_template("home").is_ok());
+    }
+
+    #[test]
+    fn test_search_path_precedence() {
+        let override_dir = tempdir().unwrap();
+        let shared_dir = tempdir().unwrap();
+        fs::write(
+            override_dir.path().join("home.tmpl"),
+            "@heading{1}{Override}",
+        )
+        .unwrap();
+        fs::write(shared_dir.path().join("home.tmpl"), "@heading{1}{Shared}").unwrap();
+        fs::write(shared_dir.path().join("about.tmpl"), "@heading{1}{About}").unwrap();
+
+        let engine = TemplateEngine::new(shared_dir.path()).with_search_path(vec![
+            override_dir.path().to_path_buf(),
+            shared_dir.path().to_path_buf(),
+        ]);
+
+        let context = TemplateContext::new();
+        let home = engine
+            .render("home", &context, &TextRenderer::new())
+            .unwrap();
+        assert!(home.contains("Override"));
+        assert!(!home.contains("Shared"));
+
+        let about = engine
+            .render("about", &context, &TextRenderer::new())
+            .unwrap();
+        assert!(about.contains("About"));
+
+        engine.clear_cache().unwrap();
+        let report = engine.preload_all().unwrap();
+        assert_eq!(report.loaded, vec!["about", "home"]);
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let override_dir = tempdir().unwrap();
+        let shared_dir = tempdir().unwrap();
+        fs::write(override_dir.path().join("home.tmpl"), "@heading{1}{A}").unwrap();
+        fs::write(shared_dir.path().join("home.tmpl"), "@heading{1}{B}").unwrap();
+        fs::write(shared_dir.path().join("about.tmpl"), "@heading{1}{C}").unwrap();
+
+        let engine = TemplateEngine::new(shared_dir.path()).with_search_path(vec![
+            override_dir.path().to_path_buf(),
+            shared_dir.path().to_path_buf(),
+        ]);
+
+        let conflicts = engine.find_conflicts().unwrap();
+        assert_eq!(
+            conflicts,
+            vec![(
+                "home".to_string(),
+                vec![
+                    override_dir.path().join("home.tmpl"),
+                    shared_dir.path().join("home.tmpl"),
+                ]
+            )]
+        );
+
+        assert!(TemplateEngine::new(shared_dir.path())
+            .find_conflicts()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_reqvar_directive() {
+        let template_dir = tempdir().unwrap();
+        fs::write(
+            template_dir.path().join("host.tmpl"),
+            "@paragraph{Host:}\n@reqvar{hostname}",
+        )
+        .unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path());
+
+        let context = TemplateContext::new().with_variable("hostname", "maxteibel-server");
+        let text = engine
+            .render("host", &context, &TextRenderer::new())
+            .unwrap();
+        assert!(text.contains("maxteibel-server"));
+
+        let html = engine
+            .render(
+                "host",
+                &TemplateContext::new().with_variable("hostname", "<b>web-1</b>"),
+                &HtmlRenderer::new(),
+            )
+            .unwrap();
+        assert!(html.contains("&lt;b&gt;web-1&lt;/b&gt;"));
+        assert!(!html.contains("<b>web-1</b>"));
+
+        let err = engine
+            .render("host", &TemplateContext::new(), &TextRenderer::new())
+            .unwrap_err();
+        assert!(matches!(err, Error::TemplateError(_)));
+        assert!(err.to_string().contains("hostname"));
+    }
+
+    #[test]
+    fn test_slot_directive() {
+        let template_dir = tempdir().unwrap();
+        fs::write(
+            template_dir.path().join("changelog.tmpl"),
+            "@heading{1}{Changelog}\n@frame{Recent}{\n  @slot{changelog}\n}",
+        )
+        .unwrap();
+
+        let engine = TemplateEngine::new(template_dir.path());
+        let context = TemplateContext::new().with_blocks(
+            "changelog",
+            vec![
+                Block::Paragraph("Added @chart directive".to_string()),
+                Block::Paragraph("Fixed trace ordering".to_string()),
+            ],
+        );
+
+        let text = engine
+            .render("changelog", &context, &TextRenderer::new())
+            .unwrap();
+        assert!(text.contains("Added @chart directive"));
+        assert!(text.contains("Fixed trace ordering"));
+
+        let empty = engine
+            .render("changelog", &TemplateContext::new(), &TextRenderer::new())
+            .unwrap();
+        assert!(empty.contains("Changelog"));
+
+        let err = engine
+            .render_strict("changelog", &TemplateContext::new(), &TextRenderer::new())
+            .unwrap_err();
+        assert!(err.to_string().contains("changelog"));
+    }
+
+    #[test]
+    fn test_context_title() {
+        let template_dir = tempdir().unwrap();
+        let engine = TemplateEngine::new(template_dir.path());
+        engine
+            .register_template("dashboard", "@heading{1}{Status}")
+            .unwrap();
+
+        let context = TemplateContext::new().with_title("My Status Page");
+
+        let html = engine
+            .render("dashboard", &context, &HtmlRenderer::new())
+            .unwrap();
+        assert!(html.contains("<title>My Status Page</title>"));
+
+        let text = engine
+            .render("dashboard", &context, &TextRenderer::new())
+            .unwrap();
+        assert!(text.starts_with("# My Status Page\n"));
+        assert!(!text.contains("dashboard"));
+    }
 }
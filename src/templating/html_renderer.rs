@@ -1,12 +1,40 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
 
 use crate::error::Result;
 use crate::models::{LogEntry, Metric, Trace};
-use crate::templating::renderer::{Block, Renderer, TemplateData};
+use crate::templating::engine::OutputFormat;
+use crate::templating::renderer::{
+    format_bytes, format_metric_value, format_timestamp, relative_timestamp, trace_status,
+    validate_timestamp_format, Block, MetricThresholds, Renderer, TemplateData, WaterfallSpan,
+};
+
+pub const DEFAULT_CHART_WIDTH: u32 = 600;
+pub const DEFAULT_CHART_HEIGHT: u32 = 200;
+const STYLESHEET_HASH_LEN: usize = 12;
 
 pub struct HtmlRenderer {
     pub additional_classes: Vec<String>,
     pub include_inline_css: bool,
+    pub layout: Option<String>,
+    pub source_styles: HashMap<String, String>,
+    pub chart_width: u32,
+    pub chart_height: u32,
+    pub timestamp_format: Option<String>,
+    pub max_metadata_items: Option<usize>,
+    pub relative_time: bool,
+    pub now: Option<DateTime<Utc>>,
+    pub value_precision: Option<usize>,
+    pub external_css: bool,
+    pub hashed_css_name: bool,
+    pub metrics_summary_row: bool,
+    pub log_level_badges: bool,
+    pub fragment: bool,
+    pub slow_threshold_ms: Option<u64>,
+    pub line_numbers: bool,
 }
 
 impl HtmlRenderer {
@@ -14,6 +42,22 @@ impl HtmlRenderer {
         Self {
             additional_classes: Vec::new(),
             include_inline_css: true,
+            layout: None,
+            source_styles: HashMap::new(),
+            chart_width: DEFAULT_CHART_WIDTH,
+            chart_height: DEFAULT_CHART_HEIGHT,
+            timestamp_format: None,
+            max_metadata_items: None,
+            relative_time: false,
+            now: None,
+            value_precision: None,
+            external_css: false,
+            hashed_css_name: false,
+            metrics_summary_row: false,
+            log_level_badges: false,
+            fragment: false,
+            slow_threshold_ms: None,
+            line_numbers: false,
         }
     }
 
@@ -27,6 +71,121 @@ impl HtmlRenderer {
         self
     }
 
+    // Links a standalone stylesheet (see `write_stylesheet`) instead of inlining
+    // the CSS. With `hashed`, the file name carries a hash of the CSS so any
+    // change busts browser caches.
+    pub fn with_external_css(mut self, hashed: bool) -> Self {
+        self.include_inline_css = false;
+        self.external_css = true;
+        self.hashed_css_name = hashed;
+        self
+    }
+
+    pub fn stylesheet_name(&self) -> String {
+        if self.hashed_css_name {
+            let hash = format!("{:x}", Sha256::digest(self.get_terminal_css().as_bytes()));
+            format!("terminal.{}.css", &hash[..STYLESHEET_HASH_LEN])
+        } else {
+            "terminal.css".to_string()
+        }
+    }
+
+    pub fn write_stylesheet<P: AsRef<Path>>(&self, output_dir: P) -> Result<PathBuf> {
+        let path = output_dir.as_ref().join(self.stylesheet_name());
+        std::fs::write(&path, self.get_terminal_css())?;
+        Ok(path)
+    }
+
+    // Besides `[[content]]`, a layout may place `[[body_classes]]`,
+    // `[[stylesheet]]` and `[[title]]` where the default page would put them.
+    pub fn with_layout(mut self, layout_html: String) -> Self {
+        self.layout = Some(layout_html);
+        self
+    }
+
+    pub fn with_source_style(mut self, source: &str, class: &str) -> Self {
+        self.source_styles
+            .insert(source.to_string(), class.to_string());
+        self
+    }
+
+    pub fn with_source_styles(mut self, source_styles: HashMap<String, String>) -> Self {
+        self.source_styles.extend(source_styles);
+        self
+    }
+
+    pub fn with_max_metadata_items(mut self, max_items: usize) -> Self {
+        self.max_metadata_items = Some(max_items);
+        self
+    }
+
+    pub fn with_value_precision(mut self, precision: Option<usize>) -> Self {
+        self.value_precision = precision;
+        self
+    }
+
+    pub fn with_metrics_summary_row(mut self, enabled: bool) -> Self {
+        self.metrics_summary_row = enabled;
+        self
+    }
+
+    // Prefixes every line of @code blocks with its line number.
+    pub fn with_line_numbers(mut self, enabled: bool) -> Self {
+        self.line_numbers = enabled;
+        self
+    }
+
+    // Untagged spans slower than this are shown with a "slow" status.
+    pub fn with_slow_threshold_ms(mut self, threshold_ms: Option<u64>) -> Self {
+        self.slow_threshold_ms = threshold_ms;
+        self
+    }
+
+    // Emits only the terminal div (and its stylesheet), without the document
+    // shell, for embedding in another page.
+    pub fn with_fragment(mut self, fragment: bool) -> Self {
+        self.fragment = fragment;
+        self
+    }
+
+    // Shows the log level as a badge instead of coloring the whole line.
+    pub fn with_log_level_badges(mut self, enabled: bool) -> Self {
+        self.log_level_badges = enabled;
+        self
+    }
+
+    pub fn with_relative_time(mut self, relative_time: bool) -> Self {
+        self.relative_time = relative_time;
+        self
+    }
+
+    pub fn with_now(mut self, now: DateTime<Utc>) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    fn display_timestamp(&self, timestamp: &str) -> String {
+        if self.relative_time {
+            if let Some(relative) = relative_timestamp(timestamp, self.now.unwrap_or_else(Utc::now))
+            {
+                return relative;
+            }
+        }
+        format_timestamp(timestamp, self.timestamp_format.as_deref())
+    }
+
+    pub fn with_timestamp_format(mut self, format: &str) -> Result<Self> {
+        validate_timestamp_format(format)?;
+        self.timestamp_format = Some(format.to_string());
+        Ok(self)
+    }
+
+    pub fn with_chart_size(mut self, width: u32, height: u32) -> Self {
+        self.chart_width = width;
+        self.chart_height = height;
+        self
+    }
+
     fn get_terminal_css(&self) -> &str {
         r#"
         * {
@@ -83,6 +242,19 @@ impl HtmlRenderer {
             color: #63c8ff;
         }
         
+        .terminal-code {
+            margin: 0.5rem 0;
+            padding: 0.5rem;
+            background-color: #2a2a2a;
+            overflow-x: auto;
+        }
+        
+        .terminal-code .ln {
+            color: #8a8a8a;
+            margin-right: 1em;
+            user-select: none;
+        }
+        
         .terminal-output {
             margin: 0.5rem 0 1.5rem 0;
             padding-left: 0.5rem;
@@ -118,6 +290,14 @@ impl HtmlRenderer {
             color: #63c8ff;
         }
         
+        .terminal-metric-warn .terminal-metric-value {
+            color: #ffac35;
+        }
+        
+        .terminal-metric-crit .terminal-metric-value {
+            color: #ff5b5b;
+        }
+        
         .terminal-log {
             padding: 0.2rem 0;
         }
@@ -138,6 +318,34 @@ impl HtmlRenderer {
             color: #ff5b5b;
         }
         
+        /* Badge colors keep at least 4.5:1 contrast with their text. */
+        .badge {
+            display: inline-block;
+            padding: 0 0.4em;
+            margin-right: 0.5em;
+            border-radius: 3px;
+            font-size: 0.8em;
+            font-weight: bold;
+            color: #1e1e1e;
+        }
+        
+        .badge-debug {
+            background-color: #bdbdbd;
+        }
+        
+        .badge-info {
+            background-color: #63c8ff;
+        }
+        
+        .badge-warning {
+            background-color: #ffac35;
+        }
+        
+        .badge-error {
+            background-color: #b71c1c;
+            color: #ffffff;
+        }
+        
         .terminal-table-container {
             margin: 0.5rem 0;
             overflow-x: auto;
@@ -173,6 +381,83 @@ impl HtmlRenderer {
             color: #63c8ff;
         }
         
+        .terminal-trace-slow .terminal-trace-duration,
+        .terminal-trace-slow .terminal-trace-details {
+            color: #ffac35;
+        }
+        
+        .terminal-deflist {
+            display: grid;
+            grid-template-columns: max-content auto;
+            gap: 0.2rem 1rem;
+            margin: 0.5rem 0;
+        }
+        
+        .terminal-deflist dt {
+            font-weight: bold;
+        }
+        
+        .terminal-deflist dd {
+            margin: 0;
+            color: #63c8ff;
+        }
+        
+        .terminal-waterfall {
+            margin: 0.5rem 0;
+        }
+        
+        .terminal-waterfall-row {
+            display: flex;
+            align-items: center;
+            padding: 0.1rem 0;
+        }
+        
+        .terminal-waterfall-label {
+            flex: 0 0 30%;
+            overflow: hidden;
+            white-space: nowrap;
+            text-overflow: ellipsis;
+        }
+        
+        .terminal-waterfall-track {
+            flex: 1;
+            height: 0.8rem;
+            background-color: #2a2a2a;
+        }
+        
+        .terminal-waterfall-bar {
+            height: 100%;
+            min-width: 2px;
+            background-color: #63c8ff;
+        }
+        
+        .terminal-waterfall-duration {
+            flex: 0 0 5rem;
+            text-align: right;
+            color: #63c8ff;
+        }
+        
+        .terminal-chart {
+            display: block;
+            max-width: 100%;
+            height: auto;
+            margin: 0.5rem 0;
+        }
+        
+        .terminal-chart-axis {
+            stroke: #3a3a3a;
+        }
+        
+        .terminal-chart-line {
+            stroke: #63c8ff;
+            stroke-width: 2;
+        }
+        
+        .terminal-chart-label {
+            fill: #9e9e9e;
+            font-size: 10px;
+        }
+        
         .terminal-trend-up::after {
             content: ' ▲';
             color: #4caf50;
@@ -339,8 +624,12 @@ impl Default for HtmlRenderer {
 }
 
 impl Renderer for HtmlRenderer {
+    fn output_format(&self) -> Option<OutputFormat> {
+        Some(OutputFormat::Html)
+    }
+
     fn render_heading(&self, level: usize, text: &str) -> Result<String> {
-        let level = level.min(6).max(1);
+        let level = level.clamp(1, 6);
         let escaped_text = self.escape_html(text);
         Ok(format!(
             "<h{0} class=\"terminal-heading terminal-heading-{0}\">{1}</h{0}>",
@@ -364,6 +653,34 @@ impl Renderer for HtmlRenderer {
         ))
     }
 
+    fn render_code(&self, code: &str) -> Result<String> {
+        let lines: Vec<&str> = code.lines().collect();
+        let width = lines.len().to_string().len();
+
+        let content = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                if self.line_numbers {
+                    format!(
+                        "<span class=\"ln\">{:>width$}</span>{}",
+                        i + 1,
+                        self.escape_html(line),
+                        width = width
+                    )
+                } else {
+                    self.escape_html(line)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok(format!(
+            "<pre class=\"terminal-code\"><code>{}</code></pre>",
+            content
+        ))
+    }
+
     fn render_output(&self, blocks: &[Block]) -> Result<String> {
         let content = self.render_blocks(blocks)?;
         Ok(format!("<div class=\"terminal-output\">{}</div>", content))
@@ -392,24 +709,41 @@ impl Renderer for HtmlRenderer {
         value: &str,
         unit: Option<&str>,
         trend: Option<f64>,
+    ) -> Result<String> {
+        self.render_metric_with_thresholds(name, value, unit, trend, &MetricThresholds::default())
+    }
+
+    fn render_metric_with_thresholds(
+        &self,
+        name: &str,
+        value: &str,
+        unit: Option<&str>,
+        trend: Option<f64>,
+        thresholds: &MetricThresholds,
     ) -> Result<String> {
         let escaped_name = self.escape_html(name);
-        let escaped_value = self.escape_html(value);
+        let escaped_value = self.escape_html(&format_metric_value(value, self.value_precision));
         let trend_class = self.get_trend_class(trend);
+        let metric_class = match thresholds.level(value) {
+            Some(level) => format!("terminal-metric terminal-metric-{}", level),
+            None => "terminal-metric".to_string(),
+        };
 
-        let value_with_unit = if let Some(u) = unit {
+        let value_with_unit = if let Some(bytes) = format_bytes(value, unit) {
+            self.escape_html(&bytes)
+        } else if let Some(u) = unit {
             format!("{} {}", escaped_value, self.escape_html(u))
         } else {
             escaped_value
         };
 
         Ok(format!(
-            "<div class=\"terminal-metric\">
+            "<div class=\"{}\">
                 <span class=\"terminal-metric-name\">{}</span>
                 <span class=\"terminal-metric-value {}\">{}
                 </span>
             </div>",
-            escaped_name, trend_class, value_with_unit
+            metric_class, escaped_name, trend_class, value_with_unit
         ))
     }
 
@@ -421,15 +755,29 @@ impl Renderer for HtmlRenderer {
         source: Option<&str>,
     ) -> Result<String> {
         let escaped_message = self.escape_html(message);
-        let log_level_class = match level.to_uppercase().as_str() {
-            "DEBUG" => "terminal-log-debug",
-            "INFO" => "terminal-log-info",
-            "WARNING" | "WARN" => "terminal-log-warning",
-            "ERROR" => "terminal-log-error",
-            _ => "terminal-log-info",
+        let log_level = match level.to_uppercase().as_str() {
+            "DEBUG" => "debug",
+            "INFO" => "info",
+            "WARNING" | "WARN" => "warning",
+            "ERROR" => "error",
+            _ => "info",
+        };
+        let (log_level_class, badge) = if self.log_level_badges {
+            (
+                "terminal-log-badged".to_string(),
+                format!(
+                    "<span class=\"badge badge-{}\">{}</span>",
+                    log_level,
+                    self.escape_html(&level.to_uppercase())
+                ),
+            )
+        } else {
+            (format!("terminal-log-{}", log_level), String::new())
         };
 
-        let prefix = match (timestamp, source) {
+        let timestamp = timestamp.map(|ts| self.display_timestamp(ts));
+
+        let prefix = match (timestamp.as_deref(), source) {
             (Some(ts), Some(src)) => {
                 format!("[{}] [{}] ", self.escape_html(ts), self.escape_html(src))
             }
@@ -438,16 +786,30 @@ impl Renderer for HtmlRenderer {
             (None, None) => String::new(),
         };
 
+        let source_class = source
+            .and_then(|src| self.source_styles.get(src))
+            .map(|class| format!(" {}", self.escape_html(class)))
+            .unwrap_or_default();
+
         Ok(format!(
-            "<div class=\"terminal-log {}\">
-                <span class=\"terminal-log-prefix\">{}</span>
+            "<div class=\"terminal-log {}{}\">
+                {}<span class=\"terminal-log-prefix\">{}</span>
                 <span class=\"terminal-log-message\">{}</span>
             </div>",
-            log_level_class, prefix, escaped_message
+            log_level_class, source_class, badge, prefix, escaped_message
         ))
     }
 
     fn render_table(&self, headers: &[String], rows: &[Vec<String>]) -> Result<String> {
+        self.render_table_with_summary(headers, rows, None)
+    }
+
+    fn render_table_with_summary(
+        &self,
+        headers: &[String],
+        rows: &[Vec<String>],
+        summary: Option<&[String]>,
+    ) -> Result<String> {
         let header_cells = headers
             .iter()
             .map(|h| format!("<th>{}</th>", self.escape_html(h)))
@@ -473,12 +835,27 @@ impl Renderer for HtmlRenderer {
             .collect::<Vec<_>>()
             .join("");
 
+        let footer = match summary {
+            Some(cells) if self.metrics_summary_row => {
+                let cells = cells
+                    .iter()
+                    .map(|c| format!("<td>{}</td>", self.escape_html(c)))
+                    .collect::<Vec<_>>()
+                    .join("");
+                format!(
+                    "<tfoot><tr class=\"terminal-table-summary\">{}</tr></tfoot>",
+                    cells
+                )
+            }
+            _ => String::new(),
+        };
+
         Ok(format!(
             "<table class=\"terminal-table\">
                 <thead>{}</thead>
-                <tbody>{}</tbody>
+                <tbody>{}</tbody>{}
             </table>",
-            header_row, table_rows
+            header_row, table_rows, footer
         ))
     }
 
@@ -491,12 +868,24 @@ impl Renderer for HtmlRenderer {
         metadata: &HashMap<String, String>,
     ) -> Result<String> {
         let escaped_name = self.escape_html(name);
+        let status = trace_status(status, duration_ms, self.slow_threshold_ms);
         let escaped_status = self.escape_html(status);
-        let escaped_start_time = self.escape_html(start_time);
+        let trace_class = if status == "slow" {
+            "terminal-trace terminal-trace-slow"
+        } else {
+            "terminal-trace"
+        };
+        let escaped_start_time = self.escape_html(&self.display_timestamp(start_time));
 
         let metadata_html = if !metadata.is_empty() {
-            let metadata_items = metadata
+            let shown = self.max_metadata_items.unwrap_or(metadata.len());
+            let hidden = metadata.len().saturating_sub(shown);
+
+            let mut metadata_items = metadata
                 .iter()
+                .collect::<BTreeMap<_, _>>()
+                .into_iter()
+                .take(shown)
                 .map(|(k, v)| {
                     format!(
                         "<span class=\"terminal-trace-metadata-item\">
@@ -509,6 +898,12 @@ impl Renderer for HtmlRenderer {
                 })
                 .collect::<Vec<_>>()
                 .join(", ");
+            if hidden > 0 {
+                metadata_items.push_str(&format!(
+                    " <span class=\"terminal-trace-metadata-more\">(+{} more)</span>",
+                    hidden
+                ));
+            }
 
             format!(
                 "<div class=\"terminal-trace-metadata\">{}</div>",
@@ -519,7 +914,7 @@ impl Renderer for HtmlRenderer {
         };
 
         Ok(format!(
-            "<div class=\"terminal-trace\">
+            "<div class=\"{}\">
                 <div class=\"terminal-trace-header\">
                     <span class=\"terminal-trace-name\">{}</span>
                     <span class=\"terminal-trace-duration\">{} ms</span>
@@ -529,7 +924,12 @@ impl Renderer for HtmlRenderer {
                 </div>
                 {}
             </div>",
-            escaped_name, duration_ms, escaped_start_time, escaped_status, metadata_html
+            trace_class,
+            escaped_name,
+            duration_ms,
+            escaped_start_time,
+            escaped_status,
+            metadata_html
         ))
     }
 
@@ -537,28 +937,176 @@ impl Renderer for HtmlRenderer {
         Ok(content.to_string())
     }
 
+    fn render_chart(&self, name: &str, points: &[(DateTime<Utc>, f64)]) -> Result<String> {
+        let escaped_name = self.escape_html(name);
+
+        let (first, last) = match (points.first(), points.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => {
+                return Ok(format!(
+                    "<div class=\"terminal-empty-message\">No data for {}</div>",
+                    escaped_name
+                ))
+            }
+        };
+
+        const PADDING: f64 = 30.0;
+        let width = self.chart_width as f64;
+        let height = self.chart_height as f64;
+        let plot_width = (width - 2.0 * PADDING).max(1.0);
+        let plot_height = (height - 2.0 * PADDING).max(1.0);
+
+        let min = points.iter().map(|(_, v)| *v).fold(f64::INFINITY, f64::min);
+        let max = points
+            .iter()
+            .map(|(_, v)| *v)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let time_span = (last.0 - first.0).num_milliseconds() as f64;
+
+        let polyline_points = points
+            .iter()
+            .map(|(timestamp, value)| {
+                let x = if time_span > 0.0 {
+                    PADDING
+                        + (*timestamp - first.0).num_milliseconds() as f64 / time_span * plot_width
+                } else {
+                    PADDING + plot_width / 2.0
+                };
+                let y = if max > min {
+                    height - PADDING - (value - min) / (max - min) * plot_height
+                } else {
+                    PADDING + plot_height / 2.0
+                };
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Ok(format!(
+            "<svg class=\"terminal-chart\" viewBox=\"0 0 {w} {h}\" width=\"{w}\" height=\"{h}\" role=\"img\" aria-label=\"{name}\">\
+             <line class=\"terminal-chart-axis\" x1=\"{p}\" y1=\"{p}\" x2=\"{p}\" y2=\"{bottom}\"/>\
+             <line class=\"terminal-chart-axis\" x1=\"{p}\" y1=\"{bottom}\" x2=\"{right}\" y2=\"{bottom}\"/>\
+             <polyline class=\"terminal-chart-line\" fill=\"none\" points=\"{points}\"/>\
+             <text class=\"terminal-chart-label\" x=\"2\" y=\"{p}\">{max}</text>\
+             <text class=\"terminal-chart-label\" x=\"2\" y=\"{bottom}\">{min}</text>\
+             </svg>",
+            w = self.chart_width,
+            h = self.chart_height,
+            name = escaped_name,
+            p = PADDING,
+            bottom = height - PADDING,
+            right = width - PADDING,
+            points = polyline_points,
+            max = max,
+            min = min,
+        ))
+    }
+
+    fn render_definition_list(&self, items: &[(String, String)]) -> Result<String> {
+        let entries = items
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "<dt>{}</dt><dd>{}</dd>",
+                    self.escape_html(key),
+                    self.escape_html(value)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("");
+
+        Ok(format!("<dl class=\"terminal-deflist\">{}</dl>", entries))
+    }
+
+    fn render_waterfall(&self, total_ms: u64, spans: &[WaterfallSpan]) -> Result<String> {
+        let percent = |ms: u64| {
+            if total_ms == 0 {
+                0.0
+            } else {
+                ms as f64 / total_ms as f64 * 100.0
+            }
+        };
+
+        let rows = spans
+            .iter()
+            .map(|span| {
+                format!(
+                    "<div class=\"terminal-waterfall-row\">\
+                     <div class=\"terminal-waterfall-label\" style=\"padding-left: {}rem\">{}</div>\
+                     <div class=\"terminal-waterfall-track\">\
+                     <div class=\"terminal-waterfall-bar\" style=\"margin-left: {:.2}%; width: {:.2}%\"></div>\
+                     </div>\
+                     <div class=\"terminal-waterfall-duration\">{}ms</div>\
+                     </div>",
+                    span.depth,
+                    self.escape_html(&span.name),
+                    percent(span.offset_ms),
+                    percent(span.duration_ms),
+                    span.duration_ms
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("");
+
+        Ok(format!("<div class=\"terminal-waterfall\">{}</div>", rows))
+    }
+
     fn render_template(&self, template_data: &TemplateData) -> Result<String> {
         let content = self.render_blocks(&template_data.blocks)?;
 
-        let class_list = if self.additional_classes.is_empty() {
-            "terminal".to_string()
-        } else {
-            format!("terminal {}", self.additional_classes.join(" "))
-        };
+        let class_list = std::iter::once("terminal".to_string())
+            .chain(self.additional_classes.iter().cloned())
+            .chain(
+                template_data
+                    .body_classes
+                    .iter()
+                    .map(|class| self.escape_html(class)),
+            )
+            .collect::<Vec<_>>()
+            .join(" ");
 
         let style_tag = if self.include_inline_css {
             format!("<style>{}</style>", self.get_terminal_css())
+        } else if self.external_css {
+            format!(
+                "<link rel=\"stylesheet\" href=\"{}\">",
+                self.stylesheet_name()
+            )
         } else {
             String::new()
         };
 
+        // Without an explicit title the page keeps using the `title` variable.
+        let title = match &template_data.title {
+            Some(title) => self.escape_html(title),
+            None => "[[title]]".to_string(),
+        };
+
+        // Layouts place the page chrome themselves; content goes in last so
+        // markers inside the rendered blocks are left for variable substitution.
+        if let Some(layout) = &self.layout {
+            return Ok(layout
+                .replace("[[body_classes]]", &class_list)
+                .replace("[[stylesheet]]", &style_tag)
+                .replace("[[title]]", &title)
+                .replace("[[content]]", &content));
+        }
+
+        if self.fragment {
+            return Ok(format!(
+                "{}<div class=\"{}\">{}
+                </div>",
+                style_tag, class_list, content
+            ));
+        }
+
         Ok(format!(
             "<!DOCTYPE html>
             <html lang=\"en\">
             <head>
                 <meta charset=\"UTF-8\">
                 <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0, maximum-scale=1.0\">
-                <title>[[title]]</title>
+                <title>{}</title>
                 {}
             </head>
             <body>
@@ -566,7 +1114,7 @@ impl Renderer for HtmlRenderer {
                 </div>
             </body>
             </html>",
-            style_tag, class_list, content
+            title, style_tag, class_list, content
         ))
     }
 
@@ -574,15 +1122,16 @@ impl Renderer for HtmlRenderer {
         let blocks: Vec<Block> = metrics
             .iter()
             .map(|m| {
-                let trend = m.get_label("trend").and_then(|t| t.parse::<f64>().ok());
+                let trend = m.trend();
 
-                let unit = m.get_label("unit").map(|s| s.as_str());
+                let unit = m.unit();
 
                 Block::Metric {
                     name: m.name.clone(),
                     value: m.value.to_string(),
                     unit: unit.map(|s| s.to_string()),
                     trend,
+                    thresholds: MetricThresholds::default(),
                 }
             })
             .collect();
@@ -629,8 +1178,8 @@ impl Renderer for HtmlRenderer {
                 vec![
                     trace.name.clone(),
                     format!("{} ms", trace.duration_ms),
-                    trace.start_time.to_rfc3339(),
-                    status,
+                    self.display_timestamp(&trace.start_time.to_rfc3339()),
+                    trace_status(&status, trace.duration_ms, self.slow_threshold_ms).to_string(),
                 ]
             })
             .collect();
@@ -642,11 +1191,12 @@ impl Renderer for HtmlRenderer {
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
+    use sha2::{Digest, Sha256};
     use std::collections::HashMap;
 
     use crate::models::{LogEntry, LogLevel, Metric, Trace};
     use crate::templating::html_renderer::HtmlRenderer;
-    use crate::templating::renderer::{Block, Renderer, TemplateData};
+    use crate::templating::renderer::{Block, MetricThresholds, Renderer, TemplateData};
 
     fn contains(haystack: &str, needle: &str) -> bool {
         haystack.contains(needle)
@@ -769,6 +1319,182 @@ mod tests {
         assert!(contains(&result_full, "db_module"));
     }
 
+    #[test]
+    fn test_render_definition_list() {
+        let renderer = HtmlRenderer::new();
+        let items = vec![
+            ("Host".to_string(), "server-1".to_string()),
+            ("<Owner>".to_string(), "ops & infra".to_string()),
+        ];
+
+        let result = renderer.render_definition_list(&items).unwrap();
+
+        assert_eq!(
+            result,
+            "<dl class=\"terminal-deflist\"><dt>Host</dt><dd>server-1</dd>\
+             <dt>&lt;Owner&gt;</dt><dd>ops &amp; infra</dd></dl>"
+        );
+    }
+
+    #[test]
+    fn test_render_template_with_layout() {
+        let layout = "<!DOCTYPE html><html><body>\
+                      <nav><a href=\"/\">Home</a></nav>\
+                      <main>[[content]]</main>\
+                      </body></html>"
+            .to_string();
+        let renderer = HtmlRenderer::new().with_layout(layout);
+
+        let template_data = TemplateData {
+            blocks: vec![Block::Paragraph("Fish & <Chips>".to_string())],
+            template_name: "home".to_string(),
+            body_classes: Vec::new(),
+            title: None,
+        };
+
+        let result = renderer.render_template(&template_data).unwrap();
+
+        assert!(contains(&result, "<nav><a href=\"/\">Home</a></nav>"));
+        assert!(contains(&result, "<main><p"));
+        assert!(contains(&result, "Fish &amp; &lt;Chips&gt;"));
+        assert!(!contains(&result, "[[content]]"));
+        assert!(!contains(&result, "<style>"));
+    }
+
+    #[test]
+    fn test_layout_page_markers() {
+        let layout = "<html><head><title>[[title]]</title>[[stylesheet]]</head>\
+                      <body><div class=\"[[body_classes]]\">[[content]]</div></body></html>"
+            .to_string();
+        let renderer = HtmlRenderer::new()
+            .with_layout(layout)
+            .with_external_css(false);
+
+        let template_data = TemplateData {
+            blocks: vec![Block::Paragraph("[[title]] is the variable".to_string())],
+            template_name: "home".to_string(),
+            body_classes: vec!["status-degraded".to_string()],
+            title: Some("Ops & Status".to_string()),
+        };
+
+        let result = renderer.render_template(&template_data).unwrap();
+
+        assert!(result.contains("<title>Ops &amp; Status</title>"));
+        assert!(result.contains(&format!(
+            "<link rel=\"stylesheet\" href=\"{}\">",
+            renderer.stylesheet_name()
+        )));
+        assert!(result.contains("class=\"terminal status-degraded\""));
+        assert!(result.contains("[[title]] is the variable"));
+    }
+
+    #[test]
+    fn test_render_log_entry_source_style() {
+        let renderer = HtmlRenderer::new().with_source_style("database", "log-source-db");
+
+        let db_log = renderer
+            .render_log_entry("Connected", "INFO", None, Some("database"))
+            .unwrap();
+        assert!(contains(
+            &db_log,
+            "class=\"terminal-log terminal-log-info log-source-db\""
+        ));
+
+        let api_log = renderer
+            .render_log_entry("GET /api/users", "INFO", None, Some("api"))
+            .unwrap();
+        assert!(contains(
+            &api_log,
+            "class=\"terminal-log terminal-log-info\""
+        ));
+    }
+
+    #[test]
+    fn test_render_trace_infers_slow_status() {
+        let renderer = HtmlRenderer::new().with_slow_threshold_ms(Some(1000));
+        let metadata = HashMap::new();
+
+        let slow = renderer
+            .render_trace(
+                "Report Export",
+                5000,
+                "2025-03-15T12:34:56Z",
+                "unknown",
+                &metadata,
+            )
+            .unwrap();
+        assert!(contains(&slow, "terminal-trace terminal-trace-slow"));
+        assert!(contains(&slow, "Status: slow"));
+
+        let tagged = renderer
+            .render_trace(
+                "Report Export",
+                5000,
+                "2025-03-15T12:34:56Z",
+                "200",
+                &metadata,
+            )
+            .unwrap();
+        assert!(contains(&tagged, "Status: 200"));
+        assert!(!contains(&tagged, "terminal-trace-slow"));
+
+        let fast = renderer
+            .render_trace(
+                "Health Check",
+                20,
+                "2025-03-15T12:34:56Z",
+                "unknown",
+                &metadata,
+            )
+            .unwrap();
+        assert!(contains(&fast, "Status: unknown"));
+    }
+
+    #[test]
+    fn test_render_template_fragment() {
+        let template_data = TemplateData {
+            blocks: vec![Block::Paragraph("Embedded".to_string())],
+            template_name: "embed".to_string(),
+            body_classes: Vec::new(),
+            title: None,
+        };
+
+        let fragment = HtmlRenderer::new()
+            .with_fragment(true)
+            .render_template(&template_data)
+            .unwrap();
+        assert!(fragment.starts_with("<style>"));
+        assert!(contains(&fragment, "<div class=\"terminal\">"));
+        assert!(contains(&fragment, "Embedded"));
+        assert!(!contains(&fragment, "<!DOCTYPE"));
+        assert!(!contains(&fragment, "<head>"));
+
+        let bare = HtmlRenderer::new()
+            .with_fragment(true)
+            .with_inline_css(false)
+            .render_template(&template_data)
+            .unwrap();
+        assert!(bare.starts_with("<div class=\"terminal\">"));
+    }
+
+    #[test]
+    fn test_render_log_entry_badges() {
+        let plain = HtmlRenderer::new()
+            .render_log_entry("Disk full", "ERROR", None, None)
+            .unwrap();
+        assert!(!contains(&plain, "badge"));
+
+        let badged = HtmlRenderer::new()
+            .with_log_level_badges(true)
+            .render_log_entry("Disk full", "error", None, None)
+            .unwrap();
+        assert!(contains(
+            &badged,
+            "<span class=\"badge badge-error\">ERROR</span>"
+        ));
+        assert!(!contains(&badged, "terminal-log-error"));
+    }
+
     #[test]
     fn test_render_table() {
         let renderer = HtmlRenderer::new();
@@ -835,6 +1561,32 @@ mod tests {
         assert!(contains(&result, "GET"));
     }
 
+    #[test]
+    fn test_render_trace_metadata_is_deterministic() {
+        let renderer = HtmlRenderer::new();
+        let render = || {
+            let metadata: HashMap<String, String> = [
+                ("status", "200"),
+                ("endpoint", "/api/users"),
+                ("method", "GET"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+            renderer
+                .render_trace("API Request", 157, "2025-03-15T12:34:56Z", "200", &metadata)
+                .unwrap()
+        };
+
+        let first = render();
+        assert_eq!(first, render());
+
+        let endpoint = first.find("endpoint").unwrap();
+        let method = first.find("method").unwrap();
+        let status = first.rfind("status").unwrap();
+        assert!(endpoint < method && method < status);
+    }
+
     #[test]
     fn test_render_block() {
         let renderer = HtmlRenderer::new();
@@ -892,6 +1644,8 @@ mod tests {
                 Block::Paragraph("System Status".to_string()),
             ],
             template_name: "dashboard".to_string(),
+            body_classes: Vec::new(),
+            title: None,
         };
 
         let result = renderer.render_template(&template_data).unwrap();
@@ -920,12 +1674,14 @@ mod tests {
                 value: 85.5,
                 timestamp: now,
                 labels: labels1,
+                unit: None,
             },
             Metric {
                 name: "Memory Usage".to_string(),
                 value: 1024.0,
                 timestamp: now,
                 labels: labels2,
+                unit: None,
             },
         ];
 
@@ -995,4 +1751,189 @@ mod tests {
         assert!(contains(&result, "45 ms"));
         assert!(contains(&result, "failed"));
     }
+
+    #[test]
+    fn test_render_chart() {
+        let renderer = HtmlRenderer::new().with_chart_size(300, 100);
+        let start = Utc::now();
+        let points: Vec<_> = [10.0, 30.0, 20.0, 50.0]
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (start + chrono::Duration::seconds(i as i64), *v))
+            .collect();
+
+        let svg = renderer.render_chart("CPU Usage", &points).unwrap();
+        assert!(contains(&svg, "viewBox=\"0 0 300 100\""));
+        assert!(contains(&svg, "<polyline"));
+        let polyline = svg.split("points=\"").nth(1).unwrap();
+        let polyline = &polyline[..polyline.find('"').unwrap()];
+        assert_eq!(polyline.split_whitespace().count(), points.len());
+        assert!(contains(&svg, ">50</text>"));
+        assert!(contains(&svg, ">10</text>"));
+
+        let single = renderer.render_chart("cpu", &points[..1]).unwrap();
+        assert!(contains(&single, "points=\"150.0,50.0\""));
+
+        let flat = vec![(start, 5.0), (start + chrono::Duration::seconds(1), 5.0)];
+        let flat_svg = renderer.render_chart("cpu", &flat).unwrap();
+        assert!(!contains(&flat_svg, "NaN"));
+
+        let empty = renderer.render_chart("cpu", &[]).unwrap();
+        assert!(contains(&empty, "No data for cpu"));
+    }
+
+    #[test]
+    fn test_custom_timestamp_format() {
+        let renderer = HtmlRenderer::new()
+            .with_timestamp_format("%Y-%m-%d %H:%M:%S%.3f %:z")
+            .unwrap();
+
+        let log = renderer
+            .render_log_entry(
+                "Server started",
+                "INFO",
+                Some("2025-03-15T12:34:56.789123Z"),
+                None,
+            )
+            .unwrap();
+        assert!(contains(&log, "[2025-03-15 12:34:56.789 +00:00]"));
+
+        let trace = renderer
+            .render_trace(
+                "API Request",
+                157,
+                "2025-03-15T12:34:56.789Z",
+                "200",
+                &HashMap::new(),
+            )
+            .unwrap();
+        assert!(contains(&trace, "2025-03-15 12:34:56.789 +00:00"));
+
+        assert!(HtmlRenderer::new().with_timestamp_format("%Q").is_err());
+    }
+
+    #[test]
+    fn test_render_trace_max_metadata_items() {
+        let metadata: HashMap<String, String> = (0..10)
+            .map(|i| (format!("key{}", i), format!("value{}", i)))
+            .collect();
+
+        let renderer = HtmlRenderer::new().with_max_metadata_items(3);
+        let result = renderer
+            .render_trace("API Request", 157, "2025-03-15T12:34:56Z", "200", &metadata)
+            .unwrap();
+
+        assert_eq!(
+            result
+                .matches("class=\"terminal-trace-metadata-item\"")
+                .count(),
+            3
+        );
+        assert!(contains(&result, ">key2<"));
+        assert!(!contains(&result, ">key3<"));
+        assert!(contains(&result, "(+7 more)"));
+    }
+
+    #[test]
+    fn test_hashed_stylesheet_name() {
+        let output_dir = tempfile::tempdir().unwrap();
+        let renderer = HtmlRenderer::new().with_external_css(true);
+
+        let path = renderer.write_stylesheet(output_dir.path()).unwrap();
+        let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let css = std::fs::read(&path).unwrap();
+        let hash = format!("{:x}", Sha256::digest(&css));
+        assert_eq!(file_name, format!("terminal.{}.css", &hash[..12]));
+
+        let template_data = TemplateData {
+            blocks: vec![Block::Paragraph("Hello".to_string())],
+            template_name: "home".to_string(),
+            body_classes: Vec::new(),
+            title: None,
+        };
+        let html = renderer.render_template(&template_data).unwrap();
+        assert!(html.contains(&format!("<link rel=\"stylesheet\" href=\"{}\">", file_name)));
+        assert!(!html.contains("<style>"));
+
+        let plain = HtmlRenderer::new().with_external_css(false);
+        assert_eq!(plain.stylesheet_name(), "terminal.css");
+    }
+
+    #[test]
+    fn test_body_classes_from_context() {
+        let template_dir = tempfile::tempdir().unwrap();
+        let engine = crate::templating::TemplateEngine::new(template_dir.path());
+        engine
+            .register_template("page", "@heading{1}{Status}")
+            .unwrap();
+
+        let context = crate::templating::TemplateContext::new().with_body_class("status-degraded");
+        let renderer = HtmlRenderer::new().with_classes(vec!["dark".to_string()]);
+
+        let html = engine.render("page", &context, &renderer).unwrap();
+        assert!(html.contains("<div class=\"terminal dark status-degraded\">"));
+    }
+
+    #[test]
+    fn test_render_metric_thresholds() {
+        let renderer = HtmlRenderer::new();
+        let thresholds = MetricThresholds {
+            warn: Some(80.0),
+            crit: Some(90.0),
+        };
+
+        let crit = renderer
+            .render_metric_with_thresholds("CPU", "95", Some("%"), None, &thresholds)
+            .unwrap();
+        assert!(crit.contains("terminal-metric terminal-metric-crit"));
+
+        let warn = renderer
+            .render_metric_with_thresholds("CPU", "85", Some("%"), None, &thresholds)
+            .unwrap();
+        assert!(warn.contains("terminal-metric-warn"));
+
+        let ok = renderer
+            .render_metric_with_thresholds("CPU", "50", Some("%"), None, &thresholds)
+            .unwrap();
+        assert!(!ok.contains("terminal-metric-warn") && !ok.contains("terminal-metric-crit"));
+    }
+
+    #[test]
+    fn test_value_precision() {
+        let fixed = HtmlRenderer::new()
+            .with_value_precision(Some(2))
+            .render_metric("CPU Usage", "78.5", Some("%"), None)
+            .unwrap();
+        assert!(fixed.contains("78.50 %"));
+
+        let natural = HtmlRenderer::new()
+            .render_metric("CPU Usage", "78.50000000000001", Some("%"), None)
+            .unwrap();
+        assert!(natural.contains("78.5 %"));
+        assert!(!natural.contains("78.50"));
+    }
+
+    #[test]
+    fn test_code_line_numbers() {
+        let template = crate::templating::Template::from_string(
+            "code",
+            &format!(
+                "@code{{\n{}\n}}",
+                (1..=12)
+                    .map(|i| format!("let x{} = {{ {} }};", i, i))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+        )
+        .unwrap();
+        let result = HtmlRenderer::new()
+            .with_line_numbers(true)
+            .render_blocks(&template.blocks)
+            .unwrap();
+
+        assert!(result.contains("<span class=\"ln\"> 1</span>let x1 = { 1 };"));
+        assert!(result.contains("<span class=\"ln\"> 9</span>"));
+        assert!(result.contains("<span class=\"ln\">12</span>let x12 = { 12 };"));
+        assert_eq!(result.matches("class=\"ln\"").count(), 12);
+    }
 }
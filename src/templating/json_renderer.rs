@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::models::{LogEntry, Metric, Trace};
+use crate::templating::engine::OutputFormat;
+use crate::templating::renderer::{Block, MetricThresholds, Renderer, TemplateData, WaterfallSpan};
+
+pub struct JsonRenderer {
+    pub pretty: bool,
+}
+
+impl JsonRenderer {
+    pub fn new() -> Self {
+        Self { pretty: false }
+    }
+
+    pub fn with_pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    fn to_json<T: Serialize + ?Sized>(&self, value: &T) -> Result<String> {
+        // Round-trip through `Value` so map keys (trace metadata, metric labels)
+        // come out sorted instead of in HashMap order.
+        let value = serde_json::to_value(value)?;
+
+        let json = if self.pretty {
+            serde_json::to_string_pretty(&value)?
+        } else {
+            serde_json::to_string(&value)?
+        };
+
+        Ok(json)
+    }
+}
+
+impl Default for JsonRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Renderer for JsonRenderer {
+    fn output_format(&self) -> Option<OutputFormat> {
+        Some(OutputFormat::Json)
+    }
+
+    fn render_heading(&self, level: usize, text: &str) -> Result<String> {
+        self.to_json(&Block::Heading {
+            level,
+            text: text.to_string(),
+        })
+    }
+
+    fn render_paragraph(&self, text: &str) -> Result<String> {
+        self.to_json(&Block::Paragraph(text.to_string()))
+    }
+
+    fn render_command_prompt(&self, command: &str) -> Result<String> {
+        self.to_json(&Block::CommandPrompt(command.to_string()))
+    }
+
+    fn render_code(&self, code: &str) -> Result<String> {
+        self.to_json(&Block::Code(code.to_string()))
+    }
+
+    fn render_output(&self, blocks: &[Block]) -> Result<String> {
+        self.to_json(&Block::Output(blocks.to_vec()))
+    }
+
+    fn render_frame(&self, title: Option<&str>, content: &str) -> Result<String> {
+        self.to_json(&Block::Frame {
+            id: None,
+            title: title.map(|t| t.to_string()),
+            content: vec![Block::Raw(content.to_string())],
+        })
+    }
+
+    fn render_metric(
+        &self,
+        name: &str,
+        value: &str,
+        unit: Option<&str>,
+        trend: Option<f64>,
+    ) -> Result<String> {
+        self.render_metric_with_thresholds(name, value, unit, trend, &MetricThresholds::default())
+    }
+
+    fn render_metric_with_thresholds(
+        &self,
+        name: &str,
+        value: &str,
+        unit: Option<&str>,
+        trend: Option<f64>,
+        thresholds: &MetricThresholds,
+    ) -> Result<String> {
+        self.to_json(&Block::Metric {
+            name: name.to_string(),
+            value: value.to_string(),
+            unit: unit.map(|u| u.to_string()),
+            trend,
+            thresholds: *thresholds,
+        })
+    }
+
+    fn render_log_entry(
+        &self,
+        message: &str,
+        level: &str,
+        timestamp: Option<&str>,
+        source: Option<&str>,
+    ) -> Result<String> {
+        self.to_json(&Block::LogEntry {
+            message: message.to_string(),
+            level: level.to_string(),
+            timestamp: timestamp.map(|t| t.to_string()),
+            source: source.map(|s| s.to_string()),
+        })
+    }
+
+    fn render_table(&self, headers: &[String], rows: &[Vec<String>]) -> Result<String> {
+        self.to_json(&Block::Table {
+            headers: headers.to_vec(),
+            rows: rows.to_vec(),
+            summary: None,
+        })
+    }
+
+    fn render_trace(
+        &self,
+        name: &str,
+        duration_ms: u64,
+        start_time: &str,
+        status: &str,
+        metadata: &HashMap<String, String>,
+    ) -> Result<String> {
+        let start_time = DateTime::parse_from_rfc3339(start_time)
+            .map_err(|e| {
+                Error::TemplateError(
+                    format!("Invalid trace start time '{}': {}", start_time, e).into(),
+                )
+            })?
+            .with_timezone(&Utc);
+
+        self.to_json(&Block::Trace {
+            name: name.to_string(),
+            duration_ms,
+            start_time,
+            status: status.to_string(),
+            metadata: metadata.clone(),
+        })
+    }
+
+    fn render_raw(&self, content: &str) -> Result<String> {
+        self.to_json(&Block::Raw(content.to_string()))
+    }
+
+    fn render_definition_list(&self, items: &[(String, String)]) -> Result<String> {
+        self.to_json(&Block::DefinitionList(items.to_vec()))
+    }
+
+    fn render_waterfall(&self, total_ms: u64, spans: &[WaterfallSpan]) -> Result<String> {
+        self.to_json(&Block::Waterfall {
+            total_ms,
+            spans: spans.to_vec(),
+        })
+    }
+
+    fn render_chart(&self, name: &str, points: &[(DateTime<Utc>, f64)]) -> Result<String> {
+        self.to_json(&Block::Chart {
+            name: name.to_string(),
+            points: points.to_vec(),
+        })
+    }
+
+    fn render_block(&self, block: &Block) -> Result<String> {
+        self.to_json(block)
+    }
+
+    fn render_blocks(&self, blocks: &[Block]) -> Result<String> {
+        self.to_json(blocks)
+    }
+
+    fn render_template(&self, template_data: &TemplateData) -> Result<String> {
+        self.to_json(template_data)
+    }
+
+    fn render_metrics(&self, metrics: &[Metric]) -> Result<String> {
+        self.to_json(metrics)
+    }
+
+    fn render_logs(&self, logs: &[LogEntry]) -> Result<String> {
+        self.to_json(logs)
+    }
+
+    fn render_traces(&self, traces: &[Trace]) -> Result<String> {
+        self.to_json(traces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::models::{LogEntry, LogLevel, Trace};
+    use crate::templating::json_renderer::JsonRenderer;
+    use crate::templating::renderer::{Block, Renderer, TemplateData};
+
+    #[test]
+    fn test_render_template() {
+        let renderer = JsonRenderer::new();
+
+        let template_data = TemplateData {
+            blocks: vec![
+                Block::Heading {
+                    level: 1,
+                    text: "Dashboard".to_string(),
+                },
+                Block::Paragraph("System Status".to_string()),
+            ],
+            template_name: "dashboard".to_string(),
+            body_classes: Vec::new(),
+            title: None,
+        };
+
+        let result = renderer.render_template(&template_data).unwrap();
+        let json: Value = serde_json::from_str(&result).unwrap();
+
+        assert_eq!(json["template_name"], "dashboard");
+        assert_eq!(json["blocks"].as_array().unwrap().len(), 2);
+        assert_eq!(json["blocks"][0]["type"], "heading");
+        assert_eq!(json["blocks"][0]["data"]["level"], 1);
+        assert_eq!(json["blocks"][0]["data"]["text"], "Dashboard");
+        assert_eq!(json["blocks"][1]["type"], "paragraph");
+        assert_eq!(json["blocks"][1]["data"], "System Status");
+    }
+
+    #[test]
+    fn test_render_nested_blocks() {
+        let renderer = JsonRenderer::new();
+
+        let block = Block::Frame {
+            id: None,
+            title: Some("Recent Logs".to_string()),
+            content: vec![Block::Output(vec![Block::Container(vec![
+                Block::CommandPrompt("uptime".to_string()),
+            ])])],
+        };
+
+        let result = renderer.render_block(&block).unwrap();
+        let json: Value = serde_json::from_str(&result).unwrap();
+
+        assert_eq!(json["type"], "frame");
+        assert_eq!(json["data"]["title"], "Recent Logs");
+
+        let output = &json["data"]["content"][0];
+        assert_eq!(output["type"], "output");
+        assert_eq!(output["data"][0]["type"], "container");
+        assert_eq!(output["data"][0]["data"][0]["type"], "command_prompt");
+        assert_eq!(output["data"][0]["data"][0]["data"], "uptime");
+    }
+
+    #[test]
+    fn test_render_logs() {
+        let renderer = JsonRenderer::new().with_pretty(true);
+        let logs = vec![LogEntry::new("Server started", LogLevel::Info, "app")];
+
+        let result = renderer.render_logs(&logs).unwrap();
+        let json: Value = serde_json::from_str(&result).unwrap();
+
+        assert!(result.contains('\n'));
+        assert_eq!(json[0]["message"], "Server started");
+        assert_eq!(json[0]["level"], "Info");
+        assert_eq!(json[0]["source"], "app");
+    }
+
+    #[test]
+    fn test_render_trace_metadata_sorted() {
+        let renderer = JsonRenderer::new();
+        let trace = Trace::new("API Request", 157)
+            .with_metadata("status", "200")
+            .with_metadata("endpoint", "/api/users")
+            .with_metadata("method", "GET");
+
+        let result = renderer.render_traces(&[trace]).unwrap();
+
+        let endpoint = result.find("\"endpoint\"").unwrap();
+        let method = result.find("\"method\"").unwrap();
+        let status = result.find("\"status\"").unwrap();
+        assert!(endpoint < method && method < status);
+    }
+}
@@ -1,12 +1,17 @@
 pub mod engine;
 pub mod html_renderer;
+pub mod json_renderer;
+pub mod null_renderer;
 pub mod renderer;
 pub mod template;
 pub mod text_renderer;
 
-pub use engine::{TemplateContext, TemplateEngine};
+pub use engine::{
+    LineEnding, OutputFormat, PostProcessor, PreloadReport, TemplateContext, TemplateEngine,
+};
 pub use html_renderer::HtmlRenderer;
-pub use renderer::{Block, Renderer, TemplateData};
+pub use json_renderer::JsonRenderer;
+pub use null_renderer::NullRenderer;
+pub use renderer::{Block, MetricThresholds, Renderer, TemplateData, WaterfallSpan};
 pub use template::Template;
-pub use text_renderer::TextRenderer;
-
+pub use text_renderer::{BoxStyle, TextRenderer};
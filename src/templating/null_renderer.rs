@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::error::Result;
+use crate::models::{LogEntry, Metric, Trace};
+use crate::templating::renderer::{Block, Renderer, TemplateData, WaterfallSpan};
+
+// Walks the whole block tree like a real renderer but produces no output.
+// Used for validation passes where only errors matter.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullRenderer;
+
+impl NullRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Renderer for NullRenderer {
+    fn render_heading(&self, _level: usize, _text: &str) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_paragraph(&self, _text: &str) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_command_prompt(&self, _command: &str) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_code(&self, _code: &str) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_output(&self, blocks: &[Block]) -> Result<String> {
+        self.render_blocks(blocks)
+    }
+
+    fn render_frame(&self, _title: Option<&str>, _content: &str) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_metric(
+        &self,
+        _name: &str,
+        _value: &str,
+        _unit: Option<&str>,
+        _trend: Option<f64>,
+    ) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_log_entry(
+        &self,
+        _message: &str,
+        _level: &str,
+        _timestamp: Option<&str>,
+        _source: Option<&str>,
+    ) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_table(&self, _headers: &[String], _rows: &[Vec<String>]) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_trace(
+        &self,
+        _name: &str,
+        _duration_ms: u64,
+        _start_time: &str,
+        _status: &str,
+        _metadata: &HashMap<String, String>,
+    ) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_raw(&self, _content: &str) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_definition_list(&self, _items: &[(String, String)]) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_waterfall(&self, _total_ms: u64, _spans: &[WaterfallSpan]) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_chart(&self, _name: &str, _points: &[(DateTime<Utc>, f64)]) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_template(&self, template_data: &TemplateData) -> Result<String> {
+        self.render_blocks(&template_data.blocks)
+    }
+
+    fn render_metrics(&self, _metrics: &[Metric]) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_logs(&self, _logs: &[LogEntry]) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn render_traces(&self, _traces: &[Trace]) -> Result<String> {
+        Ok(String::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template_is_empty() {
+        let template_data = TemplateData {
+            template_name: "test".to_string(),
+            blocks: vec![
+                Block::Heading {
+                    level: 1,
+                    text: "Title".to_string(),
+                },
+                Block::Frame {
+                    id: None,
+                    title: Some("Logs".to_string()),
+                    content: vec![Block::Output(vec![Block::Paragraph("nested".to_string())])],
+                },
+            ],
+            body_classes: Vec::new(),
+            title: None,
+        };
+
+        assert_eq!(
+            NullRenderer::new().render_template(&template_data).unwrap(),
+            ""
+        );
+    }
+}
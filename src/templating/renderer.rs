@@ -1,8 +1,14 @@
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::models::{LogEntry, Metric, Trace};
+use crate::templating::engine::OutputFormat;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone)]
+// Adjacently tagged because newtype variants such as `Paragraph(String)` cannot
+// be represented with an internal tag.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
 pub enum Block {
     Heading {
         level: usize,
@@ -13,9 +19,14 @@ pub enum Block {
 
     CommandPrompt(String),
 
+    Code(String),
+
     Output(Vec<Block>),
 
     Frame {
+        // Set with `@frame#id{...}` so the frame can be rendered on its own.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
         title: Option<String>,
         content: Vec<Block>,
     },
@@ -25,6 +36,8 @@ pub enum Block {
         value: String,
         unit: Option<String>,
         trend: Option<f64>,
+        #[serde(default)]
+        thresholds: MetricThresholds,
     },
 
     LogEntry {
@@ -37,12 +50,14 @@ pub enum Block {
     Table {
         headers: Vec<String>,
         rows: Vec<Vec<String>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        summary: Option<Vec<String>>,
     },
 
     Trace {
         name: String,
         duration_ms: u64,
-        start_time: String,
+        start_time: DateTime<Utc>,
         status: String,
         metadata: HashMap<String, String>,
     },
@@ -50,21 +65,119 @@ pub enum Block {
     Raw(String),
 
     Container(Vec<Block>),
+
+    DefinitionList(Vec<(String, String)>),
+
+    Waterfall {
+        total_ms: u64,
+        spans: Vec<WaterfallSpan>,
+    },
+
+    Chart {
+        name: String,
+        points: Vec<(DateTime<Utc>, f64)>,
+    },
+
+    // Shown only when the named context collection has no entries.
+    Empty {
+        collection: String,
+        content: Vec<Block>,
+    },
+}
+
+impl Block {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Block::Heading { .. } => "heading",
+            Block::Paragraph(_) => "paragraph",
+            Block::CommandPrompt(_) => "command_prompt",
+            Block::Code(_) => "code",
+            Block::Output(_) => "output",
+            Block::Frame { .. } => "frame",
+            Block::Metric { .. } => "metric",
+            Block::LogEntry { .. } => "log_entry",
+            Block::Table { .. } => "table",
+            Block::Trace { .. } => "trace",
+            Block::Raw(_) => "raw",
+            Block::Container(_) => "container",
+            Block::Empty { .. } => "empty",
+            Block::DefinitionList(_) => "definition_list",
+            Block::Waterfall { .. } => "waterfall",
+            Block::Chart { .. } => "chart",
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricThresholds {
+    pub warn: Option<f64>,
+    pub crit: Option<f64>,
+}
+
+impl MetricThresholds {
+    pub fn is_empty(&self) -> bool {
+        self.warn.is_none() && self.crit.is_none()
+    }
+
+    // Returns "crit" or "warn" when the value exceeds the corresponding level.
+    // Non-numeric values never cross a threshold.
+    pub fn level(&self, value: &str) -> Option<&'static str> {
+        let value = value.trim().parse::<f64>().ok()?;
+
+        if self.crit.is_some_and(|crit| value > crit) {
+            Some("crit")
+        } else if self.warn.is_some_and(|warn| value > warn) {
+            Some("warn")
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaterfallSpan {
+    pub name: String,
+    pub depth: usize,
+    pub offset_ms: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TemplateData {
     pub blocks: Vec<Block>,
     pub template_name: String,
+    // Extra classes for the page container, e.g. `status-degraded`.
+    #[serde(default)]
+    pub body_classes: Vec<String>,
+    // Display title for the page header, when it should differ from the name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl TemplateData {
+    pub fn display_title(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.template_name)
+    }
 }
 
 pub trait Renderer {
+    // Renderers that produce a site output format report it so engine post
+    // processors can target it.
+    fn output_format(&self) -> Option<OutputFormat> {
+        None
+    }
+
     fn render_heading(&self, level: usize, text: &str) -> Result<String>;
 
     fn render_paragraph(&self, text: &str) -> Result<String>;
 
     fn render_command_prompt(&self, command: &str) -> Result<String>;
 
+    // Renderers without a dedicated code style show the code as plain text.
+    fn render_code(&self, code: &str) -> Result<String> {
+        self.render_paragraph(code)
+    }
+
     fn render_output(&self, blocks: &[Block]) -> Result<String>;
 
     fn render_frame(&self, title: Option<&str>, content: &str) -> Result<String>;
@@ -77,6 +190,17 @@ pub trait Renderer {
         trend: Option<f64>,
     ) -> Result<String>;
 
+    fn render_metric_with_thresholds(
+        &self,
+        name: &str,
+        value: &str,
+        unit: Option<&str>,
+        trend: Option<f64>,
+        _thresholds: &MetricThresholds,
+    ) -> Result<String> {
+        self.render_metric(name, value, unit, trend)
+    }
+
     fn render_log_entry(
         &self,
         message: &str,
@@ -87,6 +211,15 @@ pub trait Renderer {
 
     fn render_table(&self, headers: &[String], rows: &[Vec<String>]) -> Result<String>;
 
+    fn render_table_with_summary(
+        &self,
+        headers: &[String],
+        rows: &[Vec<String>],
+        _summary: Option<&[String]>,
+    ) -> Result<String> {
+        self.render_table(headers, rows)
+    }
+
     fn render_trace(
         &self,
         name: &str,
@@ -98,14 +231,61 @@ pub trait Renderer {
 
     fn render_raw(&self, content: &str) -> Result<String>;
 
+    // Falls back to a two-column table for renderers without a list style.
+    fn render_definition_list(&self, items: &[(String, String)]) -> Result<String> {
+        let headers = vec!["Term".to_string(), "Description".to_string()];
+        let rows: Vec<Vec<String>> = items
+            .iter()
+            .map(|(term, description)| vec![term.clone(), description.clone()])
+            .collect();
+        self.render_table(&headers, &rows)
+    }
+
+    // Falls back to a table of spans, indented by depth, for renderers without
+    // a timeline style.
+    fn render_waterfall(&self, _total_ms: u64, spans: &[WaterfallSpan]) -> Result<String> {
+        let headers = vec![
+            "Span".to_string(),
+            "Offset".to_string(),
+            "Duration".to_string(),
+        ];
+        let rows: Vec<Vec<String>> = spans
+            .iter()
+            .map(|span| {
+                vec![
+                    format!("{}{}", "  ".repeat(span.depth), span.name),
+                    format!("{} ms", span.offset_ms),
+                    format!("{} ms", span.duration_ms),
+                ]
+            })
+            .collect();
+        self.render_table(&headers, &rows)
+    }
+
+    // Falls back to a table of the data points for renderers that cannot draw.
+    fn render_chart(&self, name: &str, points: &[(DateTime<Utc>, f64)]) -> Result<String> {
+        let headers = vec!["Time".to_string(), name.to_string()];
+        let rows: Vec<Vec<String>> = points
+            .iter()
+            .map(|(time, value)| {
+                vec![
+                    time.to_rfc3339(),
+                    format_metric_value(&value.to_string(), None),
+                ]
+            })
+            .collect();
+        self.render_table(&headers, &rows)
+    }
+
     fn render_block(&self, block: &Block) -> Result<String> {
         match block {
             Block::Heading { level, text } => self.render_heading(*level, text),
             Block::Paragraph(text) => self.render_paragraph(text),
             Block::CommandPrompt(command) => self.render_command_prompt(command),
+            Block::Code(code) => self.render_code(code),
             Block::Output(blocks) => self.render_output(blocks),
-            Block::Frame { title, content } => {
-                let rendered_content = self.render_blocks(content)?;
+            Block::Frame { title, content, .. } => {
+                let rendered_content = self.render_frame_content(content)?;
                 self.render_frame(title.as_deref(), &rendered_content)
             }
             Block::Metric {
@@ -113,34 +293,66 @@ pub trait Renderer {
                 value,
                 unit,
                 trend,
-            } => self.render_metric(name, value, unit.as_deref(), *trend),
+                thresholds,
+            } => {
+                self.render_metric_with_thresholds(name, value, unit.as_deref(), *trend, thresholds)
+            }
             Block::LogEntry {
                 message,
                 level,
                 timestamp,
                 source,
             } => self.render_log_entry(message, level, timestamp.as_deref(), source.as_deref()),
-            Block::Table { headers, rows } => self.render_table(headers, rows),
+            Block::Table {
+                headers,
+                rows,
+                summary,
+            } => self.render_table_with_summary(headers, rows, summary.as_deref()),
             Block::Trace {
                 name,
                 duration_ms,
                 start_time,
                 status,
                 metadata,
-            } => self.render_trace(name, *duration_ms, start_time, status, metadata),
+            } => self.render_trace(
+                name,
+                *duration_ms,
+                &start_time.to_rfc3339(),
+                status,
+                metadata,
+            ),
             Block::Raw(content) => self.render_raw(content),
             Block::Container(blocks) => self.render_blocks(blocks),
+            Block::DefinitionList(items) => self.render_definition_list(items),
+            Block::Waterfall { total_ms, spans } => self.render_waterfall(*total_ms, spans),
+            Block::Chart { name, points } => self.render_chart(name, points),
+            // The engine replaces these once it knows whether the collection is
+            // empty; without that context there is nothing to show.
+            Block::Empty { .. } => Ok(String::new()),
         }
     }
 
+    // The template name is filled in by the engine, which is the only layer that knows it.
     fn render_blocks(&self, blocks: &[Block]) -> Result<String> {
         let mut result = String::new();
         for block in blocks {
-            result.push_str(&self.render_block(block)?);
+            let rendered = self.render_block(block).map_err(|e| match e {
+                Error::RenderError { .. } => e,
+                e => Error::RenderError {
+                    template: String::new(),
+                    block_kind: block.kind().to_string(),
+                    message: e.to_string(),
+                },
+            })?;
+            result.push_str(&rendered);
         }
         Ok(result)
     }
 
+    fn render_frame_content(&self, blocks: &[Block]) -> Result<String> {
+        self.render_blocks(blocks)
+    }
+
     fn render_template(&self, template_data: &TemplateData) -> Result<String>;
 
     fn render_metrics(&self, metrics: &[Metric]) -> Result<String>;
@@ -149,3 +361,371 @@ pub trait Renderer {
 
     fn render_traces(&self, traces: &[Trace]) -> Result<String>;
 }
+
+pub(crate) fn validate_timestamp_format(format: &str) -> Result<()> {
+    use std::fmt::Write;
+
+    let mut sample = String::new();
+    write!(sample, "{}", Utc::now().format(format))
+        .map_err(|_| Error::ConfigError(format!("Invalid timestamp format '{}'", format)))
+}
+
+// Timestamps reach renderers as RFC3339 strings; anything else is shown verbatim.
+pub(crate) fn format_timestamp(timestamp: &str, format: Option<&str>) -> String {
+    match (format, DateTime::parse_from_rfc3339(timestamp)) {
+        (Some(format), Ok(parsed)) => parsed.with_timezone(&Utc).format(format).to_string(),
+        _ => timestamp.to_string(),
+    }
+}
+
+// Returns None beyond 24 hours so callers fall back to the absolute timestamp.
+pub(crate) fn relative_timestamp(timestamp: &str, now: DateTime<Utc>) -> Option<String> {
+    let timestamp = DateTime::parse_from_rfc3339(timestamp).ok()?;
+    let elapsed = now.signed_duration_since(timestamp.with_timezone(&Utc));
+
+    match elapsed.num_seconds() {
+        seconds if seconds < 60 => Some("just now".to_string()),
+        seconds if seconds < 3600 => Some(format!("{}m ago", seconds / 60)),
+        seconds if seconds < 86400 => Some(format!("{}h ago", seconds / 3600)),
+        _ => None,
+    }
+}
+
+// Spans without an explicit status reach renderers as "unknown". Those running
+// longer than the threshold are reported as "slow" instead.
+pub(crate) fn trace_status(status: &str, duration_ms: u64, slow_threshold_ms: Option<u64>) -> &str {
+    match slow_threshold_ms {
+        Some(threshold)
+            if duration_ms > threshold && (status.is_empty() || status == "unknown") =>
+        {
+            "slow"
+        }
+        _ => status,
+    }
+}
+
+// With a precision the value gets exactly that many decimals. Without one, the
+// value renders in its shortest form with float noise such as
+// `78.50000000000001` rounded away, so `1024.0` and `1024` both render as "1024"
+// and `0.0000001` keeps its digits. Non-numeric values pass through.
+pub(crate) fn format_metric_value(value: &str, precision: Option<usize>) -> String {
+    let number = match value.trim().parse::<f64>() {
+        Ok(number) if number.is_finite() => number,
+        _ => return value.to_string(),
+    };
+
+    if let Some(precision) = precision {
+        return format!("{:.*}", precision, number);
+    }
+
+    // Rounding to 15 significant digits drops the noise in the last bits; the
+    // shortest round-trip formatting then keeps tiny values such as `1e-7`.
+    let rounded = format!("{:.14e}", number).parse::<f64>().unwrap_or(number);
+    match rounded.to_string().as_str() {
+        "-0" => "0".to_string(),
+        formatted => formatted.to_string(),
+    }
+}
+
+// Values of metrics whose unit is `bytes` are humanized to B/KiB/MiB/GiB/TiB with
+// at most one decimal, so `1536` renders as "1.5 KiB". Returns `None` for other
+// units or non-numeric values, which keep their raw rendering.
+pub(crate) fn format_bytes(value: &str, unit: Option<&str>) -> Option<String> {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    if unit != Some("bytes") {
+        return None;
+    }
+
+    let mut number = value.trim().parse::<f64>().ok().filter(|n| n.is_finite())?;
+    let mut index = 0;
+    while number.abs() >= 1024.0 && index < UNITS.len() - 1 {
+        number /= 1024.0;
+        index += 1;
+    }
+
+    Some(format!(
+        "{} {}",
+        format_metric_value(&format!("{:.1}", number), None),
+        UNITS[index]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templating::engine::{TemplateContext, TemplateEngine};
+    use crate::templating::null_renderer::NullRenderer;
+
+    struct FailingParagraphRenderer(NullRenderer);
+
+    impl Renderer for FailingParagraphRenderer {
+        fn render_heading(&self, level: usize, text: &str) -> Result<String> {
+            self.0.render_heading(level, text)
+        }
+
+        fn render_paragraph(&self, _text: &str) -> Result<String> {
+            Err(Error::GenerationError("paragraph exploded".to_string()))
+        }
+
+        fn render_command_prompt(&self, command: &str) -> Result<String> {
+            self.0.render_command_prompt(command)
+        }
+
+        fn render_output(&self, blocks: &[Block]) -> Result<String> {
+            self.render_blocks(blocks)
+        }
+
+        fn render_frame(&self, title: Option<&str>, content: &str) -> Result<String> {
+            self.0.render_frame(title, content)
+        }
+
+        fn render_metric(
+            &self,
+            name: &str,
+            value: &str,
+            unit: Option<&str>,
+            trend: Option<f64>,
+        ) -> Result<String> {
+            self.0.render_metric(name, value, unit, trend)
+        }
+
+        fn render_log_entry(
+            &self,
+            message: &str,
+            level: &str,
+            timestamp: Option<&str>,
+            source: Option<&str>,
+        ) -> Result<String> {
+            self.0.render_log_entry(message, level, timestamp, source)
+        }
+
+        // Reports the table shape so the default table fallbacks can be checked.
+        fn render_table(&self, headers: &[String], rows: &[Vec<String>]) -> Result<String> {
+            Ok(format!("{}:{}", headers.join("|"), rows.len()))
+        }
+
+        fn render_trace(
+            &self,
+            name: &str,
+            duration_ms: u64,
+            start_time: &str,
+            status: &str,
+            metadata: &HashMap<String, String>,
+        ) -> Result<String> {
+            self.0
+                .render_trace(name, duration_ms, start_time, status, metadata)
+        }
+
+        fn render_raw(&self, content: &str) -> Result<String> {
+            self.0.render_raw(content)
+        }
+
+        fn render_template(&self, template_data: &TemplateData) -> Result<String> {
+            self.render_blocks(&template_data.blocks)
+        }
+
+        fn render_metrics(&self, metrics: &[Metric]) -> Result<String> {
+            self.0.render_metrics(metrics)
+        }
+
+        fn render_logs(&self, logs: &[LogEntry]) -> Result<String> {
+            self.0.render_logs(logs)
+        }
+
+        fn render_traces(&self, traces: &[Trace]) -> Result<String> {
+            self.0.render_traces(traces)
+        }
+    }
+
+    #[test]
+    fn test_default_code_falls_back_to_paragraph() {
+        let renderer = FailingParagraphRenderer(NullRenderer::new());
+        match renderer.render_blocks(&[Block::Code("let x = 1;".to_string())]) {
+            Err(Error::RenderError { block_kind, .. }) => assert_eq!(block_kind, "code"),
+            other => panic!("Expected render error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_default_table_fallbacks() {
+        let renderer = FailingParagraphRenderer(NullRenderer::new());
+        let blocks = vec![Block::DefinitionList(vec![(
+            "Host".to_string(),
+            "server-1".to_string(),
+        )])];
+        assert_eq!(
+            renderer.render_blocks(&blocks).unwrap(),
+            "Term|Description:1"
+        );
+
+        let waterfall = Block::Waterfall {
+            total_ms: 157,
+            spans: vec![WaterfallSpan {
+                name: "API Request".to_string(),
+                depth: 0,
+                offset_ms: 0,
+                duration_ms: 157,
+            }],
+        };
+        assert_eq!(
+            renderer.render_block(&waterfall).unwrap(),
+            "Span|Offset|Duration:1"
+        );
+
+        let chart = Block::Chart {
+            name: "CPU".to_string(),
+            points: vec![
+                ("2025-03-15T12:00:00Z".parse().unwrap(), 42.0),
+                ("2025-03-15T12:01:00Z".parse().unwrap(), 58.5),
+            ],
+        };
+        assert_eq!(renderer.render_block(&chart).unwrap(), "Time|CPU:2");
+    }
+
+    #[test]
+    fn test_render_error_names_block_kind() {
+        let renderer = FailingParagraphRenderer(NullRenderer::new());
+        let blocks = vec![
+            Block::Heading {
+                level: 1,
+                text: "Status".to_string(),
+            },
+            Block::Frame {
+                id: None,
+                title: Some("Details".to_string()),
+                content: vec![Block::Paragraph("boom".to_string())],
+            },
+        ];
+
+        match renderer.render_blocks(&blocks) {
+            Err(Error::RenderError { block_kind, .. }) => assert_eq!(block_kind, "paragraph"),
+            other => panic!("Expected render error, got {:?}", other),
+        }
+
+        let template_dir = tempfile::tempdir().unwrap();
+        let engine = TemplateEngine::new(template_dir.path());
+        engine
+            .register_template("status", "@heading{1}{Status}\nAll systems go")
+            .unwrap();
+
+        let err = engine
+            .render("status", &TemplateContext::new(), &renderer)
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::RenderError { template, block_kind, .. }
+                if template == "status" && block_kind == "paragraph"
+        ));
+        assert!(err.to_string().contains("paragraph exploded"));
+        assert_eq!(err.to_string().matches("Render error").count(), 1);
+    }
+
+    #[test]
+    fn test_block_serde_round_trip() {
+        let mut metadata = HashMap::new();
+        metadata.insert("endpoint".to_string(), "/api/users".to_string());
+
+        let template_data = TemplateData {
+            blocks: vec![
+                Block::Heading {
+                    level: 1,
+                    text: "Dashboard".to_string(),
+                },
+                Block::Paragraph("System Status".to_string()),
+                Block::Output(vec![Block::Metric {
+                    name: "CPU Usage".to_string(),
+                    value: "78.5".to_string(),
+                    unit: Some("%".to_string()),
+                    trend: Some(2.3),
+                    thresholds: MetricThresholds::default(),
+                }]),
+                Block::Frame {
+                    id: None,
+                    title: None,
+                    content: vec![Block::LogEntry {
+                        message: "Server started".to_string(),
+                        level: "INFO".to_string(),
+                        timestamp: None,
+                        source: Some("app".to_string()),
+                    }],
+                },
+                Block::Table {
+                    headers: vec!["Name".to_string()],
+                    rows: vec![vec!["Server 1".to_string()]],
+                    summary: None,
+                },
+                Block::Trace {
+                    name: "API Request".to_string(),
+                    duration_ms: 157,
+                    start_time: "2025-03-15T12:34:56Z".parse().unwrap(),
+                    status: "200".to_string(),
+                    metadata,
+                },
+                Block::Container(vec![Block::Raw("@metrics".to_string())]),
+                Block::DefinitionList(vec![("Host".to_string(), "server-1".to_string())]),
+                Block::Waterfall {
+                    total_ms: 157,
+                    spans: vec![WaterfallSpan {
+                        name: "API Request".to_string(),
+                        depth: 0,
+                        offset_ms: 0,
+                        duration_ms: 157,
+                    }],
+                },
+                Block::Chart {
+                    name: "CPU Usage".to_string(),
+                    points: vec![("2025-03-15T12:34:56Z".parse().unwrap(), 78.5)],
+                },
+            ],
+            template_name: "dashboard".to_string(),
+            body_classes: Vec::new(),
+            title: None,
+        };
+
+        let json = serde_json::to_string(&template_data).unwrap();
+        let restored: TemplateData = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, template_data);
+    }
+
+    #[test]
+    fn test_relative_timestamp() {
+        let now: DateTime<Utc> = "2025-03-15T12:00:00Z".parse().unwrap();
+
+        assert_eq!(
+            relative_timestamp("2025-03-15T11:59:30Z", now).as_deref(),
+            Some("just now")
+        );
+        assert_eq!(
+            relative_timestamp("2025-03-15T11:55:00Z", now).as_deref(),
+            Some("5m ago")
+        );
+        assert_eq!(
+            relative_timestamp("2025-03-15T10:00:00Z", now).as_deref(),
+            Some("2h ago")
+        );
+        assert_eq!(relative_timestamp("2025-03-14T11:00:00Z", now), None);
+        assert_eq!(relative_timestamp("yesterday", now), None);
+    }
+
+    #[test]
+    fn test_format_metric_value() {
+        assert_eq!(format_metric_value("78.5", Some(2)), "78.50");
+        assert_eq!(format_metric_value("1024", Some(1)), "1024.0");
+        assert_eq!(format_metric_value("78.5", None), "78.5");
+        assert_eq!(format_metric_value("78.50000000000001", None), "78.5");
+        assert_eq!(format_metric_value("1024.0", None), "1024");
+        assert_eq!(format_metric_value("0.0000001", None), "0.0000001");
+        assert_eq!(format_metric_value("n/a", Some(2)), "n/a");
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes("1536", Some("bytes")).unwrap(), "1.5 KiB");
+        assert_eq!(format_bytes("1073741824", Some("bytes")).unwrap(), "1 GiB");
+        assert_eq!(format_bytes("512", Some("bytes")).unwrap(), "512 B");
+        assert!(format_bytes("1536", Some("MB")).is_none());
+        assert!(format_bytes("n/a", Some("bytes")).is_none());
+    }
+}
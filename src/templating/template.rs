@@ -1,10 +1,17 @@
 use crate::error::{Error, Result};
-use crate::templating::renderer::{Block, TemplateData};
-use std::collections::HashMap;
+use crate::models::parse_trend;
+use crate::templating::renderer::{Block, MetricThresholds, TemplateData};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
-#[derive(Debug, Clone)]
+// Deepest allowed nesting of @frame/@output blocks. Keeps recursive parsing and
+// rendering well clear of the stack limit.
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Template {
     pub name: String,
     pub content: String,
@@ -14,10 +21,24 @@ pub struct Template {
 
 impl Template {
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_file_with_max_depth(path, DEFAULT_MAX_DEPTH)
+    }
+
+    pub fn from_file_with_max_depth<P: AsRef<Path>>(path: P, max_depth: usize) -> Result<Self> {
         let path_ref = path.as_ref();
-        let content = fs::read_to_string(path_ref).map_err(|e| {
+        let bytes = fs::read(path_ref).map_err(|e| {
             Error::TemplateError(format!("Failed to read template file: {}", e).into())
         })?;
+        let content = String::from_utf8(bytes).map_err(|e| {
+            Error::TemplateError(
+                format!(
+                    "Template file '{}' is not valid UTF-8: invalid byte at offset {}",
+                    path_ref.display(),
+                    e.utf8_error().valid_up_to()
+                )
+                .into(),
+            )
+        })?;
 
         let name = path_ref
             .file_stem()
@@ -25,14 +46,22 @@ impl Template {
             .unwrap_or("unnamed")
             .to_string();
 
-        Self::from_string(name, content)
+        Self::from_string_with_max_depth(name, content, max_depth)
     }
 
     pub fn from_string<S: Into<String>>(name: S, content: S) -> Result<Self> {
+        Self::from_string_with_max_depth(name, content, DEFAULT_MAX_DEPTH)
+    }
+
+    pub fn from_string_with_max_depth<S: Into<String>>(
+        name: S,
+        content: S,
+        max_depth: usize,
+    ) -> Result<Self> {
         let name = name.into();
         let content = content.into();
 
-        let mut parser = TemplateParser::new(&content);
+        let mut parser = TemplateParser::new(&content, max_depth);
         let blocks = parser.parse()?;
 
         Ok(Self {
@@ -43,10 +72,18 @@ impl Template {
         })
     }
 
+    // The parsed block tree, before any directive expansion. Stable API for
+    // external tooling such as linters and editor plugins.
+    pub fn ast(&self) -> &[Block] {
+        &self.blocks
+    }
+
     pub fn to_template_data(&self) -> TemplateData {
         TemplateData {
             blocks: self.blocks.clone(),
             template_name: self.name.clone(),
+            body_classes: Vec::new(),
+            title: None,
         }
     }
 
@@ -68,6 +105,173 @@ impl Template {
 
         result
     }
+
+    pub fn required_variables(&self) -> HashSet<String> {
+        let mut variables: HashSet<String> = find_placeholders(&self.content).into_iter().collect();
+
+        collect_block_variables(&self.blocks, &mut variables);
+        variables
+    }
+
+    // Strict checks the lenient parser lets through, e.g. table rows whose
+    // cell count doesn't match the headers.
+    pub fn validate(&self) -> Result<()> {
+        validate_blocks(&self.blocks).map_err(|e| match e {
+            Error::TemplateError(msg) => Error::TemplateError(
+                format!("Template '{}' failed validation: {}", self.name, msg).into(),
+            ),
+            other => other,
+        })
+    }
+}
+
+fn validate_blocks(blocks: &[Block]) -> Result<()> {
+    for block in blocks {
+        match block {
+            Block::Output(nested) | Block::Container(nested) => validate_blocks(nested)?,
+            Block::Frame { content, .. } | Block::Empty { content, .. } => {
+                validate_blocks(content)?
+            }
+            Block::Table { headers, rows, .. } if !headers.is_empty() => {
+                if let Some((index, row)) = rows
+                    .iter()
+                    .enumerate()
+                    .find(|(_, row)| row.len() != headers.len())
+                {
+                    return Err(Error::TemplateError(
+                        format!(
+                            "table row {} has {} cells but headers define {} columns",
+                            index + 1,
+                            row.len(),
+                            headers.len()
+                        )
+                        .into(),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+pub(crate) fn find_placeholders(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut remaining = text;
+
+    while let Some(start) = remaining.find("[[") {
+        remaining = &remaining[start + 2..];
+        match remaining.find("]]") {
+            Some(end) => {
+                let name = remaining[..end].trim();
+                if is_variable_name(name) {
+                    names.push(name.to_string());
+                }
+                remaining = &remaining[end + 2..];
+            }
+            None => break,
+        }
+    }
+
+    names
+}
+
+fn reqvar_name(text: &str) -> Option<&str> {
+    text.trim()
+        .strip_prefix("@reqvar{")?
+        .strip_suffix('}')
+        .map(str::trim)
+}
+
+fn collect_block_variables(blocks: &[Block], variables: &mut HashSet<String>) {
+    for block in blocks {
+        let texts: Vec<&str> = match block {
+            // `@reqvar{name}` stays a raw directive until the engine expands it.
+            Block::Raw(text) if reqvar_name(text).is_some() => {
+                variables.extend(reqvar_name(text).map(str::to_string));
+                Vec::new()
+            }
+            Block::Heading { text, .. } => vec![text],
+            Block::Paragraph(text)
+            | Block::CommandPrompt(text)
+            | Block::Code(text)
+            | Block::Raw(text) => vec![text],
+            Block::Output(nested) | Block::Container(nested) => {
+                collect_block_variables(nested, variables);
+                Vec::new()
+            }
+            Block::Frame { title, content, .. } => {
+                collect_block_variables(content, variables);
+                title.iter().map(|t| t.as_str()).collect()
+            }
+            Block::Empty { content, .. } => {
+                collect_block_variables(content, variables);
+                Vec::new()
+            }
+            Block::Metric {
+                name, value, unit, ..
+            } => {
+                let mut texts = vec![name.as_str(), value.as_str()];
+                texts.extend(unit.as_deref());
+                texts
+            }
+            Block::LogEntry {
+                message,
+                level,
+                timestamp,
+                source,
+            } => {
+                let mut texts = vec![message.as_str(), level.as_str()];
+                texts.extend(timestamp.as_deref());
+                texts.extend(source.as_deref());
+                texts
+            }
+            Block::Table { headers, rows, .. } => headers
+                .iter()
+                .chain(rows.iter().flatten())
+                .map(|cell| cell.as_str())
+                .collect(),
+            Block::Trace {
+                name,
+                status,
+                metadata,
+                ..
+            } => {
+                let mut texts = vec![name.as_str(), status.as_str()];
+                texts.extend(metadata.values().map(|v| v.as_str()));
+                texts
+            }
+            Block::DefinitionList(items) => items
+                .iter()
+                .flat_map(|(key, value)| [key.as_str(), value.as_str()])
+                .collect(),
+            Block::Waterfall { spans, .. } => spans.iter().map(|s| s.name.as_str()).collect(),
+            Block::Chart { name, .. } => vec![name.as_str()],
+        };
+
+        for text in texts {
+            variables.extend(find_placeholders(text));
+        }
+    }
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+
+    value
+        .parse::<i64>()
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
 }
 
 struct TemplateParser<'a> {
@@ -75,15 +279,25 @@ struct TemplateParser<'a> {
     position: usize,
     line: usize,
     column: usize,
+    directive_start: (usize, usize),
+    depth: usize,
+    max_depth: usize,
 }
 
 impl<'a> TemplateParser<'a> {
-    fn new(content: &'a str) -> Self {
+    fn new(content: &'a str, max_depth: usize) -> Self {
+        Self::at(content, 1, 1, 0, max_depth)
+    }
+
+    fn at(content: &'a str, line: usize, column: usize, depth: usize, max_depth: usize) -> Self {
         Self {
             content,
             position: 0,
-            line: 1,
-            column: 1,
+            line,
+            column,
+            directive_start: (line, column),
+            depth,
+            max_depth,
         }
     }
 
@@ -104,13 +318,23 @@ impl<'a> TemplateParser<'a> {
             return Ok(None);
         }
 
+        self.directive_start = (self.line, self.column);
+
         if self.match_char('@') {
             if self.match_string("metrics") {
-                return Ok(Some(Block::Raw("@metrics".to_string())));
+                return self.parse_collection_directive("@metrics");
             } else if self.match_string("logs") {
                 return Ok(Some(Block::Raw("@logs".to_string())));
             } else if self.match_string("traces") {
                 return Ok(Some(Block::Raw("@traces".to_string())));
+            } else if self.match_string("waterfall") {
+                return self.parse_collection_directive("@waterfall");
+            } else if self.match_string("chart") {
+                return self.parse_collection_directive("@chart");
+            } else if self.match_string("json") {
+                return self.parse_collection_directive("@json");
+            } else if self.match_string("slot") {
+                return self.parse_collection_directive("@slot");
             } else {
                 return self.parse_directive();
             }
@@ -124,6 +348,19 @@ impl<'a> TemplateParser<'a> {
         }
     }
 
+    fn parse_collection_directive(&mut self, directive: &str) -> Result<Option<Block>> {
+        let mut content = directive.to_string();
+
+        while self.peek() == '{' {
+            self.expect_char('{')?;
+            let arg = self.parse_until('}')?;
+            self.expect_char('}')?;
+            content.push_str(&format!("{{{}}}", arg.trim()));
+        }
+
+        Ok(Some(Block::Raw(content)))
+    }
+
     fn parse_directive(&mut self) -> Result<Option<Block>> {
         let directive = self.parse_identifier();
 
@@ -138,6 +375,11 @@ impl<'a> TemplateParser<'a> {
             "table" => self.parse_table_directive(),
             "trace" => self.parse_trace_directive(),
             "raw" => self.parse_raw_directive(),
+            "var" => self.parse_var_directive(),
+            "reqvar" => self.parse_reqvar_directive(),
+            "deflist" => self.parse_deflist_directive(),
+            "code" => self.parse_code_directive(),
+            "empty" => self.parse_empty_directive(),
             _ => Err(Error::TemplateError(
                 format!(
                     "Unknown directive @{} at line {}, column {}",
@@ -162,6 +404,15 @@ impl<'a> TemplateParser<'a> {
                 .into(),
             )
         })?;
+        if !(1..=6).contains(&level) {
+            return Err(Error::TemplateError(
+                format!(
+                    "Heading level {} at line {}, column {} is out of range, expected 1-6",
+                    level, self.line, self.column
+                )
+                .into(),
+            ));
+        }
 
         self.expect_char('{')?;
         let text = self.parse_until('}')?;
@@ -187,47 +438,98 @@ impl<'a> TemplateParser<'a> {
     }
 
     fn parse_output_directive(&mut self) -> Result<Option<Block>> {
-        self.expect_char('{')?;
+        let nested_blocks = self.parse_nested_blocks("output")?;
 
-        let start_pos = self.position;
-        let mut depth = 1;
+        Ok(Some(Block::Output(nested_blocks)))
+    }
 
-        while depth > 0 && !self.is_at_end() {
-            let c = self.advance();
-            if c == '{' {
-                depth += 1;
-            } else if c == '}' {
-                depth -= 1;
+    fn parse_frame_directive(&mut self) -> Result<Option<Block>> {
+        let id = if self.match_char('#') {
+            let id = self.parse_identifier();
+            if id.is_empty() {
+                return Err(Error::TemplateError(
+                    format!(
+                        "Missing frame id after '#' at line {}, column {}",
+                        self.line, self.column
+                    )
+                    .into(),
+                ));
             }
-        }
+            Some(id)
+        } else {
+            None
+        };
 
-        if depth > 0 {
+        let title = if self.peek() == '{' {
+            self.expect_char('{')?;
+            let title = self.parse_until('}')?;
+            self.expect_char('}')?;
+            Some(title)
+        } else {
+            None
+        };
+
+        let nested_blocks = self.parse_nested_blocks("frame")?;
+
+        Ok(Some(Block::Frame {
+            id,
+            title,
+            content: nested_blocks,
+        }))
+    }
+
+    fn parse_empty_directive(&mut self) -> Result<Option<Block>> {
+        self.expect_char('{')?;
+        let collection = self.parse_until('}')?.trim().to_string();
+        self.expect_char('}')?;
+
+        if !matches!(collection.as_str(), "metrics" | "logs" | "traces") {
             return Err(Error::TemplateError(
                 format!(
-                    "Unclosed output block at line {}, column {}",
-                    self.line, self.column
+                    "Unknown collection '{}' in @empty at line {}, column {}, expected metrics, logs or traces",
+                    collection, self.line, self.column
                 )
                 .into(),
             ));
         }
 
-        let content = &self.content[start_pos..(self.position - 1)];
+        let content = self.parse_nested_blocks("empty")?;
+
+        Ok(Some(Block::Empty {
+            collection,
+            content,
+        }))
+    }
 
-        let mut nested_parser = TemplateParser::new(content);
-        let nested_blocks = nested_parser.parse()?;
+    fn parse_nested_blocks(&mut self, kind: &str) -> Result<Vec<Block>> {
+        let (open_line, open_column) = self.directive_start;
 
-        Ok(Some(Block::Output(nested_blocks)))
+        if self.depth >= self.max_depth {
+            return Err(Error::TemplateError(
+                format!(
+                    "{} block at line {}, column {} exceeds the maximum nesting depth of {}",
+                    kind, open_line, open_column, self.max_depth
+                )
+                .into(),
+            ));
+        }
+
+        let (start_line, start_column) = (self.line, self.column + 1);
+        let content = self.parse_balanced(kind)?;
+
+        let mut nested_parser = TemplateParser::at(
+            content,
+            start_line,
+            start_column,
+            self.depth + 1,
+            self.max_depth,
+        );
+        nested_parser.parse()
     }
 
-    fn parse_frame_directive(&mut self) -> Result<Option<Block>> {
-        let title = if self.peek() == '{' {
-            self.expect_char('{')?;
-            let title = self.parse_until('}')?;
-            self.expect_char('}')?;
-            Some(title)
-        } else {
-            None
-        };
+    // Returns everything between a `{` and its matching `}`.
+    fn parse_balanced(&mut self, kind: &str) -> Result<&'a str> {
+        let (open_line, open_column) = self.directive_start;
 
         self.expect_char('{')?;
 
@@ -246,22 +548,23 @@ impl<'a> TemplateParser<'a> {
         if depth > 0 {
             return Err(Error::TemplateError(
                 format!(
-                    "Unclosed frame block at line {}, column {}",
-                    self.line, self.column
+                    "Unclosed {} block opened at line {}, column {}; reached end of input at line {}, column {}",
+                    kind, open_line, open_column, self.line, self.column
                 )
                 .into(),
             ));
         }
 
-        let content = &self.content[start_pos..(self.position - 1)];
+        Ok(&self.content[start_pos..(self.position - 1)])
+    }
 
-        let mut nested_parser = TemplateParser::new(content);
-        let nested_blocks = nested_parser.parse()?;
+    // The body is kept verbatim apart from the newlines around it, so code may
+    // contain balanced braces.
+    fn parse_code_directive(&mut self) -> Result<Option<Block>> {
+        let code = self.parse_balanced("code")?;
+        let code = code.trim_start_matches(['\r', '\n']).trim_end();
 
-        Ok(Some(Block::Frame {
-            title,
-            content: nested_blocks,
-        }))
+        Ok(Some(Block::Code(code.to_string())))
     }
 
     fn parse_metric_directive(&mut self) -> Result<Option<Block>> {
@@ -287,7 +590,7 @@ impl<'a> TemplateParser<'a> {
             let trend_str = self.parse_until('}')?;
             self.expect_char('}')?;
 
-            Some(trend_str.parse::<f64>().map_err(|_| {
+            Some(parse_trend(&trend_str).ok_or_else(|| {
                 Error::TemplateError(
                     format!(
                         "Invalid trend value '{}' at line {}, column {}",
@@ -300,11 +603,21 @@ impl<'a> TemplateParser<'a> {
             None
         };
 
+        let thresholds = if self.peek() == '{' {
+            self.expect_char('{')?;
+            let spec = self.parse_until('}')?;
+            self.expect_char('}')?;
+            self.parse_thresholds(&spec)?
+        } else {
+            MetricThresholds::default()
+        };
+
         Ok(Some(Block::Metric {
             name,
             value,
             unit,
             trend,
+            thresholds,
         }))
     }
 
@@ -343,6 +656,33 @@ impl<'a> TemplateParser<'a> {
         }))
     }
 
+    // Accepts a comma-separated list such as `warn:80,crit:90`.
+    fn parse_thresholds(&self, spec: &str) -> Result<MetricThresholds> {
+        let mut thresholds = MetricThresholds::default();
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let parsed = entry
+                .split_once(':')
+                .and_then(|(level, limit)| Some((level.trim(), limit.trim().parse::<f64>().ok()?)));
+
+            match parsed {
+                Some(("warn", limit)) => thresholds.warn = Some(limit),
+                Some(("crit", limit)) => thresholds.crit = Some(limit),
+                _ => {
+                    return Err(Error::TemplateError(
+                        format!(
+                            "Invalid metric threshold '{}' at line {}, column {}",
+                            entry, self.line, self.column
+                        )
+                        .into(),
+                    ))
+                }
+            }
+        }
+
+        Ok(thresholds)
+    }
+
     fn parse_table_directive(&mut self) -> Result<Option<Block>> {
         self.expect_char('{')?;
 
@@ -376,7 +716,11 @@ impl<'a> TemplateParser<'a> {
 
         self.expect_char('}')?;
 
-        Ok(Some(Block::Table { headers, rows }))
+        Ok(Some(Block::Table {
+            headers,
+            rows,
+            summary: None,
+        }))
     }
 
     fn parse_trace_directive(&mut self) -> Result<Option<Block>> {
@@ -399,9 +743,19 @@ impl<'a> TemplateParser<'a> {
         })?;
 
         self.expect_char('{')?;
-        let start_time = self.parse_until('}')?;
+        let start_time_str = self.parse_until('}')?;
         self.expect_char('}')?;
 
+        let start_time = parse_timestamp(start_time_str.trim()).ok_or_else(|| {
+            Error::TemplateError(
+                format!(
+                    "Invalid start time '{}' at line {}, column {}",
+                    start_time_str, self.line, self.column
+                )
+                .into(),
+            )
+        })?;
+
         self.expect_char('{')?;
         let status = self.parse_until('}')?;
         self.expect_char('}')?;
@@ -442,6 +796,68 @@ impl<'a> TemplateParser<'a> {
         Ok(Some(Block::Raw(content)))
     }
 
+    fn parse_deflist_directive(&mut self) -> Result<Option<Block>> {
+        let (open_line, open_column) = self.directive_start;
+        self.expect_char('{')?;
+
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+
+            if self.is_at_end() {
+                return Err(Error::TemplateError(
+                    format!(
+                        "Unclosed deflist block opened at line {}, column {}; reached end of input at line {}, column {}",
+                        open_line, open_column, self.line, self.column
+                    )
+                    .into(),
+                ));
+            }
+
+            if self.match_char('}') {
+                break;
+            }
+
+            if !self.match_string("@def") {
+                return Err(Error::TemplateError(
+                    format!(
+                        "Expected @def inside @deflist at line {}, column {}",
+                        self.line, self.column
+                    )
+                    .into(),
+                ));
+            }
+
+            self.expect_char('{')?;
+            let key = self.parse_until('}')?;
+            self.expect_char('}')?;
+
+            self.expect_char('{')?;
+            let value = self.parse_until('}')?;
+            self.expect_char('}')?;
+
+            items.push((key.trim().to_string(), value.trim().to_string()));
+        }
+
+        Ok(Some(Block::DefinitionList(items)))
+    }
+
+    fn parse_var_directive(&mut self) -> Result<Option<Block>> {
+        self.expect_char('{')?;
+        let name = self.parse_until('}')?;
+        self.expect_char('}')?;
+
+        Ok(Some(Block::Raw(format!("[[{}]]", name.trim()))))
+    }
+
+    fn parse_reqvar_directive(&mut self) -> Result<Option<Block>> {
+        self.expect_char('{')?;
+        let name = self.parse_until('}')?;
+        self.expect_char('}')?;
+
+        Ok(Some(Block::Raw(format!("@reqvar{{{}}}", name.trim()))))
+    }
+
     fn parse_text(&mut self) -> String {
         let start_pos = self.position;
 
@@ -620,6 +1036,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_template_from_file_invalid_utf8() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"@paragraph{caf\xe9}").unwrap();
+
+        let err = Template::from_file(temp_file.path()).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains(&temp_file.path().display().to_string()));
+        assert!(message.contains("not valid UTF-8"));
+        assert!(message.contains("offset 14"));
+    }
+
     #[test]
     fn test_template_from_file() {
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -678,7 +1106,7 @@ mod tests {
         assert_eq!(template.blocks.len(), 1);
 
         match &template.blocks[0] {
-            Block::Frame { title, content } => {
+            Block::Frame { title, content, .. } => {
                 assert_eq!(title.as_ref().unwrap(), "Frame Title");
                 assert_eq!(content.len(), 2);
 
@@ -714,6 +1142,7 @@ mod tests {
                 value,
                 unit,
                 trend,
+                ..
             } => {
                 assert_eq!(name, "CPU Usage");
                 assert_eq!(value, "78.5");
@@ -724,6 +1153,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ast() {
+        let template =
+            Template::from_string("test", "@heading{1}{Status}\n@output{\n@metrics\n}").unwrap();
+
+        assert_eq!(
+            template.ast(),
+            &[
+                Block::Heading {
+                    level: 1,
+                    text: "Status".to_string(),
+                },
+                Block::Output(vec![Block::Raw("@metrics".to_string())]),
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_table() {
         let template_content = "@table{\n@headers{Name|Value|Status}\n@row{Server 1|10.5|OK}\n@row{Server 2|8.3|Warning}\n}";
@@ -732,7 +1178,7 @@ mod tests {
         assert_eq!(template.blocks.len(), 1);
 
         match &template.blocks[0] {
-            Block::Table { headers, rows } => {
+            Block::Table { headers, rows, .. } => {
                 assert_eq!(headers.len(), 3);
                 assert_eq!(headers[0], "Name");
                 assert_eq!(headers[1], "Value");
@@ -795,4 +1241,183 @@ mod tests {
         let result = Template::from_string("test", template_content);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_heading_level_range() {
+        for content in ["@heading{0}{Title}", "@heading{7}{Title}"] {
+            let err = Template::from_string("test", content)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("out of range, expected 1-6"), "{}", err);
+        }
+
+        let template = Template::from_string("test", "@heading{3}{Title}").unwrap();
+        assert_eq!(
+            template.blocks[0],
+            Block::Heading {
+                level: 3,
+                text: "Title".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_unclosed_frame_reports_opening_position() {
+        let template_content = "@heading{1}{Dashboard}\n\n@frame{Logs}{\n  @output{\n    @logs\n  }\n\n@paragraph{Footer}";
+        let err = Template::from_string("test", template_content)
+            .unwrap_err()
+            .to_string();
+
+        assert!(
+            err.contains("Unclosed frame block opened at line 3, column 1"),
+            "{}",
+            err
+        );
+        assert!(err.contains("reached end of input at line 8"), "{}", err);
+
+        let template_content = "@frame{Outer}{\n  @output{\n    @unknown{x}\n  }\n}";
+        let err = Template::from_string("test", template_content)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("at line 3"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_deflist() {
+        let template_content = "@deflist{\n  @def{Host}{server-1}\n  @def{Uptime}{[[uptime]]}\n}";
+        let template = Template::from_string("test", template_content).unwrap();
+
+        assert_eq!(
+            template.blocks,
+            vec![Block::DefinitionList(vec![
+                ("Host".to_string(), "server-1".to_string()),
+                ("Uptime".to_string(), "[[uptime]]".to_string()),
+            ])]
+        );
+        assert!(template.required_variables().contains("uptime"));
+
+        let result = Template::from_string("test", "@deflist{ @paragraph{nope} }");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_trace_start_time() {
+        let expected: DateTime<Utc> = "2025-03-15T12:34:56Z".parse().unwrap();
+
+        for start_time in [
+            "2025-03-15T12:34:56Z",
+            "2025-03-15T14:34:56+02:00",
+            "1742042096000",
+        ] {
+            let template_content = format!("@trace{{API Request}}{{157}}{{{}}}{{200}}", start_time);
+            let template = Template::from_string("test", template_content.as_str()).unwrap();
+
+            match &template.blocks[0] {
+                Block::Trace { start_time, .. } => assert_eq!(*start_time, expected),
+                _ => panic!("Expected trace block"),
+            }
+        }
+
+        let err = Template::from_string("test", "@trace{API Request}{157}{not-a-date}{200}")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("Invalid start time 'not-a-date' at line 1"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_required_variables() {
+        let template_content =
+            "@heading{1}{Status}\n@var{a}\n@paragraph{Host: [[b]] | Empty: [[ ]]}\n@reqvar{c}";
+        let template = Template::from_string("test", template_content).unwrap();
+
+        let variables = template.required_variables();
+        assert_eq!(variables.len(), 3);
+        assert!(variables.contains("a"));
+        assert!(variables.contains("b"));
+        assert!(variables.contains("c"));
+
+        match &template.blocks[1] {
+            Block::Raw(content) => assert_eq!(content, "[[a]]"),
+            _ => panic!("Expected raw block"),
+        }
+    }
+
+    #[test]
+    fn test_parse_metrics_arguments() {
+        let template = Template::from_string("test", "@metrics{ table }\n@metrics").unwrap();
+
+        assert_eq!(template.blocks.len(), 2);
+
+        match &template.blocks[0] {
+            Block::Raw(content) => assert_eq!(content, "@metrics{table}"),
+            _ => panic!("Expected raw block"),
+        }
+
+        match &template.blocks[1] {
+            Block::Raw(content) => assert_eq!(content, "@metrics"),
+            _ => panic!("Expected raw block"),
+        }
+    }
+
+    #[test]
+    fn test_validate_table_row_cell_count() {
+        let matching = Template::from_string(
+            "matching",
+            "@table{\n@headers{Name|Value|Status}\n@row{Server 1|10.5|OK}\n}",
+        )
+        .unwrap();
+        assert!(matching.validate().is_ok());
+
+        // Lenient parsing still accepts the mismatched row; only validate() rejects it.
+        let mismatched = Template::from_string(
+            "mismatched",
+            "@frame{Hosts}{\n@table{\n@headers{Name|Value|Status}\n@row{Server 1|10.5|OK}\n@row{Server 2|8.3}\n}\n}",
+        )
+        .unwrap();
+        let err = mismatched.validate().unwrap_err().to_string();
+        assert!(err.contains("mismatched"));
+        assert!(err.contains("row 2 has 2 cells"));
+    }
+
+    #[test]
+    fn test_parse_metric_thresholds() {
+        let template =
+            Template::from_string("test", "@metric{CPU}{95}{%}{+2}{warn:80,crit:90}").unwrap();
+
+        match &template.blocks[0] {
+            Block::Metric { thresholds, .. } => {
+                assert_eq!(thresholds.warn, Some(80.0));
+                assert_eq!(thresholds.crit, Some(90.0));
+                assert_eq!(thresholds.level("95"), Some("crit"));
+            }
+            _ => panic!("Expected metric block"),
+        }
+
+        assert!(Template::from_string("test", "@metric{CPU}{95}{%}{+2}{panic:99}").is_err());
+    }
+
+    #[test]
+    fn test_max_nesting_depth() {
+        let nested = |depth: usize| {
+            format!(
+                "{}@paragraph{{deep}}{}",
+                "@output{".repeat(depth),
+                "}".repeat(depth)
+            )
+        };
+
+        assert!(Template::from_string_with_max_depth("ok", &nested(3), 3).is_ok());
+
+        let err = Template::from_string_with_max_depth("deep", &nested(4), 3)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("maximum nesting depth of 3"));
+
+        // Far past the default limit: must fail cleanly instead of overflowing the stack.
+        assert!(Template::from_string("huge", &nested(10_000)).is_err());
+    }
 }
@@ -1,14 +1,44 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
 
 use crate::error::Result;
 use crate::models::{LogEntry, Metric, Trace};
-use crate::templating::renderer::{Block, Renderer, TemplateData};
-
-const DEFAULT_TERMINAL_WIDTH: usize = 100;
+use crate::templating::engine::OutputFormat;
+use crate::templating::renderer::{
+    format_bytes, format_metric_value, format_timestamp, relative_timestamp, trace_status,
+    validate_timestamp_format, Block, MetricThresholds, Renderer, TemplateData, WaterfallSpan,
+};
+
+pub const DEFAULT_TERMINAL_WIDTH: usize = 100;
+pub const DEFAULT_HEADER_FORMAT: &str = "# {name}\n\n";
+pub const DEFAULT_FOOTER_FORMAT: &str = "\n--- Generated at {timestamp} ---\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoxStyle {
+    #[default]
+    Unicode,
+    Ascii,
+    Rounded,
+    Double,
+}
 
 pub struct TextRenderer {
     pub terminal_width: usize,
     pub ascii_only: bool,
+    pub box_style: BoxStyle,
+    pub source_tags: HashMap<String, String>,
+    pub timestamp_format: Option<String>,
+    pub show_trend_value: bool,
+    pub max_metadata_items: Option<usize>,
+    pub relative_time: bool,
+    pub now: Option<DateTime<Utc>>,
+    pub header_format: Option<String>,
+    pub footer_format: Option<String>,
+    pub value_precision: Option<usize>,
+    pub metrics_summary_row: bool,
+    pub slow_threshold_ms: Option<u64>,
+    pub line_numbers: bool,
 }
 
 impl TextRenderer {
@@ -16,6 +46,19 @@ impl TextRenderer {
         Self {
             terminal_width: DEFAULT_TERMINAL_WIDTH,
             ascii_only: false,
+            box_style: BoxStyle::default(),
+            source_tags: HashMap::new(),
+            timestamp_format: None,
+            show_trend_value: false,
+            max_metadata_items: None,
+            relative_time: false,
+            now: None,
+            header_format: Some(DEFAULT_HEADER_FORMAT.to_string()),
+            footer_format: Some(DEFAULT_FOOTER_FORMAT.to_string()),
+            value_precision: None,
+            metrics_summary_row: false,
+            slow_threshold_ms: None,
+            line_numbers: false,
         }
     }
 
@@ -29,11 +72,116 @@ impl TextRenderer {
         self
     }
 
+    // `with_ascii_only(true)` still forces ASCII borders whatever the style.
+    pub fn with_box_style(mut self, box_style: BoxStyle) -> Self {
+        self.box_style = box_style;
+        self
+    }
+
+    pub fn with_source_tag(mut self, source: &str, tag: &str) -> Self {
+        self.source_tags.insert(source.to_string(), tag.to_string());
+        self
+    }
+
+    pub fn with_trend_value(mut self, show: bool) -> Self {
+        self.show_trend_value = show;
+        self
+    }
+
+    pub fn with_max_metadata_items(mut self, max_items: usize) -> Self {
+        self.max_metadata_items = Some(max_items);
+        self
+    }
+
+    pub fn with_value_precision(mut self, precision: Option<usize>) -> Self {
+        self.value_precision = precision;
+        self
+    }
+
+    pub fn with_metrics_summary_row(mut self, enabled: bool) -> Self {
+        self.metrics_summary_row = enabled;
+        self
+    }
+
+    // Prefixes every line of @code blocks with its line number.
+    pub fn with_line_numbers(mut self, enabled: bool) -> Self {
+        self.line_numbers = enabled;
+        self
+    }
+
+    // Untagged spans slower than this are shown with a "slow" status.
+    pub fn with_slow_threshold_ms(mut self, threshold_ms: Option<u64>) -> Self {
+        self.slow_threshold_ms = threshold_ms;
+        self
+    }
+
+    pub fn with_relative_time(mut self, relative_time: bool) -> Self {
+        self.relative_time = relative_time;
+        self
+    }
+
+    pub fn with_now(mut self, now: DateTime<Utc>) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    // `{name}` and `{timestamp}` are interpolated; `None` disables the header.
+    pub fn with_header_format(mut self, format: Option<&str>) -> Self {
+        self.header_format = format.map(str::to_string);
+        self
+    }
+
+    pub fn with_footer_format(mut self, format: Option<&str>) -> Self {
+        self.footer_format = format.map(str::to_string);
+        self
+    }
+
+    fn display_timestamp(&self, timestamp: &str) -> String {
+        if self.relative_time {
+            if let Some(relative) = relative_timestamp(timestamp, self.now.unwrap_or_else(Utc::now))
+            {
+                return relative;
+            }
+        }
+        format_timestamp(timestamp, self.timestamp_format.as_deref())
+    }
+
+    pub fn with_timestamp_format(mut self, format: &str) -> Result<Self> {
+        validate_timestamp_format(format)?;
+        self.timestamp_format = Some(format.to_string());
+        Ok(self)
+    }
+
+    fn nested(&self) -> Self {
+        Self {
+            terminal_width: self.terminal_width.saturating_sub(4),
+            ascii_only: self.ascii_only,
+            box_style: self.box_style,
+            source_tags: self.source_tags.clone(),
+            timestamp_format: self.timestamp_format.clone(),
+            show_trend_value: self.show_trend_value,
+            max_metadata_items: self.max_metadata_items,
+            relative_time: self.relative_time,
+            now: self.now,
+            header_format: self.header_format.clone(),
+            footer_format: self.footer_format.clone(),
+            value_precision: self.value_precision,
+            metrics_summary_row: self.metrics_summary_row,
+            slow_threshold_ms: self.slow_threshold_ms,
+            line_numbers: self.line_numbers,
+        }
+    }
+
     fn box_chars(&self) -> BoxChars {
         if self.ascii_only {
-            BoxChars::ascii()
-        } else {
-            BoxChars::unicode()
+            return BoxChars::ascii();
+        }
+
+        match self.box_style {
+            BoxStyle::Unicode => BoxChars::unicode(),
+            BoxStyle::Ascii => BoxChars::ascii(),
+            BoxStyle::Rounded => BoxChars::rounded(),
+            BoxStyle::Double => BoxChars::double(),
         }
     }
 
@@ -50,13 +198,11 @@ impl TextRenderer {
         for word in text.split_whitespace() {
             let word_width = word.chars().count();
 
-            if current_width + word_width + 1 > available_width {
-                if !current_line.is_empty() {
-                    result.push_str(&current_line);
-                    result.push('\n');
-                    current_line = " ".repeat(indent);
-                    current_width = indent;
-                }
+            if current_width + word_width + 1 > available_width && !current_line.is_empty() {
+                result.push_str(&current_line);
+                result.push('\n');
+                current_line = " ".repeat(indent);
+                current_width = indent;
             }
 
             if !current_line.is_empty() {
@@ -86,7 +232,10 @@ impl TextRenderer {
             .unwrap_or(0);
 
         let title_width = title.map(|t| t.chars().count() + 2).unwrap_or(0);
-        let box_width = max_line_width.max(title_width).min(self.terminal_width - 4) + 4;
+        let box_width = max_line_width
+            .max(title_width)
+            .min(self.terminal_width.saturating_sub(4))
+            + 4;
 
         let mut result = String::new();
 
@@ -134,112 +283,80 @@ impl TextRenderer {
     }
 
     fn format_table(&self, headers: &[String], rows: &[Vec<String>]) -> String {
-        if headers.is_empty() && rows.is_empty() {
+        let column_count = headers
+            .len()
+            .max(rows.iter().map(|row| row.len()).max().unwrap_or(0));
+
+        if column_count == 0 {
             return String::new();
         }
 
         let box_chars = self.box_chars();
 
-        let mut col_widths = vec![
-            0;
-            headers
-                .len()
-                .max(rows.iter().map(|row| row.len()).max().unwrap_or(0))
-        ];
-
-        for (i, header) in headers.iter().enumerate() {
-            if i < col_widths.len() {
-                col_widths[i] = col_widths[i].max(header.chars().count());
-            }
-        }
-
-        for row in rows {
+        let mut col_widths = vec![0; column_count];
+        for row in std::iter::once(headers).chain(rows.iter().map(|row| row.as_slice())) {
             for (i, cell) in row.iter().enumerate() {
-                if i < col_widths.len() {
-                    col_widths[i] = col_widths[i].max(cell.chars().count());
-                }
+                col_widths[i] = col_widths[i].max(cell.chars().count());
             }
         }
 
-        let mut result = String::new();
-
-        result.push_str(&format!("{}", box_chars.top_left));
+        let separator = |left: &str, middle: &str, right: &str| {
+            let segments: Vec<String> = col_widths
+                .iter()
+                .map(|width| box_chars.horizontal.repeat(width + 2))
+                .collect();
+            format!("{}{}{}\n", left, segments.join(middle), right)
+        };
 
-        for (i, width) in col_widths.iter().enumerate() {
-            result.push_str(&box_chars.horizontal.repeat(width + 2));
-            if i < col_widths.len() - 1 {
-                result.push_str(&box_chars.tee_down);
-            }
-        }
+        let format_row = |row: &[String]| {
+            let cells: Vec<String> = col_widths
+                .iter()
+                .enumerate()
+                .map(|(i, width)| {
+                    let cell = row.get(i).map(|c| c.as_str()).unwrap_or("");
+                    format!(" {:<width$} ", cell, width = width)
+                })
+                .collect();
+            format!(
+                "{}{}{}\n",
+                box_chars.vertical,
+                cells.join(&box_chars.vertical),
+                box_chars.vertical
+            )
+        };
 
-        result.push_str(&format!("{}\n", box_chars.top_right));
+        let mut result = separator(
+            &box_chars.top_left,
+            &box_chars.tee_down,
+            &box_chars.top_right,
+        );
 
         if !headers.is_empty() {
-            result.push_str(&box_chars.vertical);
-
-            for (i, header) in headers.iter().enumerate() {
-                let width = if i < col_widths.len() {
-                    col_widths[i]
-                } else {
-                    0
-                };
-                result.push_str(&format!(" {:<width$} ", header, width = width));
-                result.push_str(&box_chars.vertical);
-            }
-
-            result.push('\n');
-
-            result.push_str(&box_chars.tee_right);
-
-            for (i, width) in col_widths.iter().enumerate() {
-                result.push_str(&box_chars.horizontal.repeat(width + 2));
-                if i < col_widths.len() - 1 {
-                    result.push_str(&box_chars.cross);
-                }
-            }
-
-            result.push_str(&format!("{}\n", box_chars.tee_left));
+            result.push_str(&format_row(headers));
+            result.push_str(&separator(
+                &box_chars.tee_right,
+                &box_chars.cross,
+                &box_chars.tee_left,
+            ));
         }
 
         for (row_idx, row) in rows.iter().enumerate() {
-            result.push_str(&box_chars.vertical);
-
-            for (i, cell) in row.iter().enumerate() {
-                let width = if i < col_widths.len() {
-                    col_widths[i]
-                } else {
-                    0
-                };
-                result.push_str(&format!(" {:<width$} ", cell, width = width));
-                result.push_str(&box_chars.vertical);
-            }
-
-            result.push('\n');
+            result.push_str(&format_row(row));
 
             if row_idx < rows.len() - 1 {
-                result.push_str(&box_chars.tee_right);
-
-                for (i, width) in col_widths.iter().enumerate() {
-                    result.push_str(&box_chars.horizontal.repeat(width + 2));
-                    if i < col_widths.len() - 1 {
-                        result.push_str(&box_chars.cross);
-                    }
-                }
-
-                result.push_str(&format!("{}\n", box_chars.tee_left));
+                result.push_str(&separator(
+                    &box_chars.tee_right,
+                    &box_chars.cross,
+                    &box_chars.tee_left,
+                ));
             }
         }
 
-        result.push_str(&format!("{}", box_chars.bottom_left));
-
-        for (i, width) in col_widths.iter().enumerate() {
-            result.push_str(&box_chars.horizontal.repeat(width + 2));
-            if i < col_widths.len() - 1 {
-                result.push_str(&box_chars.tee_up);
-            }
-        }
-
-        result.push_str(&format!("{}\n", box_chars.bottom_right));
+        result.push_str(&separator(
+            &box_chars.bottom_left,
+            &box_chars.tee_up,
+            &box_chars.bottom_right,
+        ));
 
         result
     }
@@ -276,6 +393,32 @@ impl BoxChars {
         }
     }
 
+    fn rounded() -> Self {
+        Self {
+            top_left: "╭".to_string(),
+            top_right: "╮".to_string(),
+            bottom_left: "╰".to_string(),
+            bottom_right: "╯".to_string(),
+            ..Self::unicode()
+        }
+    }
+
+    fn double() -> Self {
+        Self {
+            horizontal: "═".to_string(),
+            vertical: "║".to_string(),
+            top_left: "╔".to_string(),
+            top_right: "╗".to_string(),
+            bottom_left: "╚".to_string(),
+            bottom_right: "╝".to_string(),
+            cross: "╬".to_string(),
+            tee_right: "╠".to_string(),
+            tee_left: "╣".to_string(),
+            tee_down: "╦".to_string(),
+            tee_up: "╩".to_string(),
+        }
+    }
+
     fn ascii() -> Self {
         Self {
             horizontal: "-".to_string(),
@@ -300,8 +443,12 @@ impl Default for TextRenderer {
 }
 
 impl Renderer for TextRenderer {
+    fn output_format(&self) -> Option<OutputFormat> {
+        Some(OutputFormat::Text)
+    }
+
     fn render_heading(&self, level: usize, text: &str) -> Result<String> {
-        let level = level.min(6).max(1);
+        let level = level.clamp(1, 6);
 
         let underline_char = match level {
             1 => "=",
@@ -332,6 +479,24 @@ impl Renderer for TextRenderer {
         Ok(format!("$ {}\n", command))
     }
 
+    // Code is never wrapped, so long lines keep their meaning.
+    fn render_code(&self, code: &str) -> Result<String> {
+        let lines: Vec<&str> = code.lines().collect();
+        let width = lines.len().to_string().len();
+
+        let mut result = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            if self.line_numbers {
+                result.push_str(&format!("{:>width$} | ", i + 1, width = width));
+            }
+            result.push_str(line);
+            result.push('\n');
+        }
+        result.push('\n');
+
+        Ok(result)
+    }
+
     fn render_output(&self, blocks: &[Block]) -> Result<String> {
         let content = self.render_blocks(blocks)?;
         Ok(format!("{}\n", content))
@@ -341,6 +506,10 @@ impl Renderer for TextRenderer {
         Ok(format!("{}\n", self.create_box(content, title)))
     }
 
+    fn render_frame_content(&self, blocks: &[Block]) -> Result<String> {
+        self.nested().render_blocks(blocks)
+    }
+
     fn render_metric(
         &self,
         name: &str,
@@ -348,19 +517,41 @@ impl Renderer for TextRenderer {
         unit: Option<&str>,
         trend: Option<f64>,
     ) -> Result<String> {
-        let value_with_unit = if let Some(u) = unit {
-            format!("{} {}", value, u)
-        } else {
-            value.to_string()
+        self.render_metric_with_thresholds(name, value, unit, trend, &MetricThresholds::default())
+    }
+
+    fn render_metric_with_thresholds(
+        &self,
+        name: &str,
+        value: &str,
+        unit: Option<&str>,
+        trend: Option<f64>,
+        thresholds: &MetricThresholds,
+    ) -> Result<String> {
+        let bytes = format_bytes(value, unit);
+        let formatted = format_metric_value(value, self.value_precision);
+        let value_with_unit = match (bytes, unit) {
+            (Some(bytes), _) => bytes,
+            (None, Some(u)) => format!("{} {}", formatted, u),
+            (None, None) => formatted,
         };
 
-        let trend_indicator = match trend {
-            Some(t) if t > 0.0 => " ▲",
-            Some(t) if t < 0.0 => " ▼",
-            _ => "",
+        let mut trend_indicator = match trend {
+            Some(t) if t > 0.0 => " ▲".to_string(),
+            Some(t) if t < 0.0 => " ▼".to_string(),
+            _ => String::new(),
+        };
+        if let Some(t) = trend.filter(|t| self.show_trend_value && *t != 0.0) {
+            trend_indicator.push_str(&format!(" {:+}", t));
+        }
+
+        let alert_marker = match thresholds.level(value) {
+            Some("crit") => " !!",
+            Some(_) => " !",
+            None => "",
         };
 
-        let formatted_value = format!("{}{}", value_with_unit, trend_indicator);
+        let formatted_value = format!("{}{}{}", value_with_unit, trend_indicator, alert_marker);
 
         let padding = self
             .terminal_width
@@ -391,6 +582,15 @@ impl Renderer for TextRenderer {
             _ => "INFO ",
         };
 
+        let source = source.map(|src| {
+            self.source_tags
+                .get(src)
+                .map(|tag| tag.as_str())
+                .unwrap_or(src)
+        });
+
+        let timestamp = timestamp.map(|ts| self.display_timestamp(ts));
+
         let prefix = match (timestamp, source) {
             (Some(ts), Some(src)) => format!("[{}] [{}] [{}] ", ts, level_str, src),
             (Some(ts), None) => format!("[{}] [{}] ", ts, level_str),
@@ -419,6 +619,130 @@ impl Renderer for TextRenderer {
         Ok(self.format_table(headers, rows))
     }
 
+    fn render_table_with_summary(
+        &self,
+        headers: &[String],
+        rows: &[Vec<String>],
+        summary: Option<&[String]>,
+    ) -> Result<String> {
+        match summary {
+            Some(cells) if self.metrics_summary_row => {
+                let mut rows = rows.to_vec();
+                rows.push(cells.to_vec());
+                Ok(self.format_table(headers, &rows))
+            }
+            _ => self.render_table(headers, rows),
+        }
+    }
+
+    fn render_waterfall(&self, total_ms: u64, spans: &[WaterfallSpan]) -> Result<String> {
+        let box_chars = self.box_chars();
+        let bar_char = if self.ascii_only { "#" } else { "█" };
+
+        let labels: Vec<String> = spans
+            .iter()
+            .map(|span| format!("{}{}", "  ".repeat(span.depth), span.name))
+            .collect();
+        let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let durations: Vec<String> = spans
+            .iter()
+            .map(|span| format!("{}ms", span.duration_ms))
+            .collect();
+        let duration_width = durations.iter().map(|d| d.len()).max().unwrap_or(0);
+
+        let bar_width = self
+            .terminal_width
+            .saturating_sub(label_width + duration_width + 4)
+            .max(10);
+        let scale = |ms: u64| {
+            if total_ms == 0 {
+                0
+            } else {
+                (ms as usize * bar_width / total_ms as usize).min(bar_width)
+            }
+        };
+
+        let mut result = String::new();
+        for ((span, label), duration) in spans.iter().zip(&labels).zip(&durations) {
+            let offset = scale(span.offset_ms).min(bar_width - 1);
+            let width = scale(span.duration_ms).clamp(1, bar_width - offset);
+
+            result.push_str(&format!(
+                "{:<label_width$} {}{}{}{}{} {:>duration_width$}\n",
+                label,
+                box_chars.vertical,
+                " ".repeat(offset),
+                bar_char.repeat(width),
+                " ".repeat(bar_width - offset - width),
+                box_chars.vertical,
+                duration,
+                label_width = label_width,
+                duration_width = duration_width
+            ));
+        }
+        result.push('\n');
+
+        Ok(result)
+    }
+
+    fn render_chart(&self, name: &str, points: &[(DateTime<Utc>, f64)]) -> Result<String> {
+        if points.is_empty() {
+            return Ok(format!("{}: no data\n\n", name));
+        }
+
+        let levels: Vec<char> = if self.ascii_only {
+            "_.-=+*#".chars().collect()
+        } else {
+            "▁▂▃▄▅▆▇█".chars().collect()
+        };
+
+        let min = points.iter().map(|(_, v)| *v).fold(f64::INFINITY, f64::min);
+        let max = points
+            .iter()
+            .map(|(_, v)| *v)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let sparkline: String = points
+            .iter()
+            .map(|(_, value)| {
+                let level = if max > min {
+                    ((value - min) / (max - min) * (levels.len() - 1) as f64).round() as usize
+                } else {
+                    levels.len() / 2
+                };
+                levels[level]
+            })
+            .collect();
+
+        Ok(format!(
+            "{}: {} (min {}, max {})\n\n",
+            name, sparkline, min, max
+        ))
+    }
+
+    fn render_definition_list(&self, items: &[(String, String)]) -> Result<String> {
+        let key_width = items
+            .iter()
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut result = String::new();
+        for (key, value) in items {
+            let padding = key_width - key.chars().count();
+            let wrapped_value = self.wrap_text(value, key_width + 2);
+            result.push_str(&format!(
+                "{}:{} {}\n",
+                key,
+                " ".repeat(padding),
+                wrapped_value
+            ));
+        }
+        result.push('\n');
+
+        Ok(result)
+    }
+
     fn render_trace(
         &self,
         name: &str,
@@ -428,13 +752,23 @@ impl Renderer for TextRenderer {
         metadata: &HashMap<String, String>,
     ) -> Result<String> {
         let mut content = format!("{} ({} ms)\n", name, duration_ms);
-        content.push_str(&format!("Started: {}, Status: {}\n", start_time, status));
+        content.push_str(&format!(
+            "Started: {}, Status: {}\n",
+            self.display_timestamp(start_time),
+            trace_status(status, duration_ms, self.slow_threshold_ms)
+        ));
 
         if !metadata.is_empty() {
+            let sorted = metadata.iter().collect::<BTreeMap<_, _>>();
+            let shown = self.max_metadata_items.unwrap_or(sorted.len());
+
             content.push_str("Metadata:\n");
-            for (key, value) in metadata.iter() {
+            for (key, value) in sorted.iter().take(shown) {
                 content.push_str(&format!("  {}: {}\n", key, value));
             }
+            if sorted.len() > shown {
+                content.push_str(&format!("  (+{} more)\n", sorted.len() - shown));
+            }
         }
 
         Ok(content)
@@ -447,12 +781,24 @@ impl Renderer for TextRenderer {
     fn render_template(&self, template_data: &TemplateData) -> Result<String> {
         let content = self.render_blocks(&template_data.blocks)?;
 
-        let header = format!("# {}\n\n", template_data.template_name);
-
-        let timestamp = chrono::Utc::now().to_rfc3339();
-        let footer = format!("\n--- Generated at {} ---\n", timestamp);
+        let timestamp = self.now.unwrap_or_else(Utc::now).to_rfc3339();
+        let interpolate = |format: &Option<String>| {
+            format
+                .as_deref()
+                .map(|format| {
+                    format
+                        .replace("{name}", template_data.display_title())
+                        .replace("{timestamp}", &timestamp)
+                })
+                .unwrap_or_default()
+        };
 
-        Ok(format!("{}{}{}", header, content, footer))
+        Ok(format!(
+            "{}{}{}",
+            interpolate(&self.header_format),
+            content,
+            interpolate(&self.footer_format)
+        ))
     }
 
     fn render_metrics(&self, metrics: &[Metric]) -> Result<String> {
@@ -463,11 +809,9 @@ impl Renderer for TextRenderer {
         let mut result = String::new();
 
         for metric in metrics {
-            let trend = metric
-                .get_label("trend")
-                .and_then(|t| t.parse::<f64>().ok());
+            let trend = metric.trend();
 
-            let unit = metric.get_label("unit").map(|s| s.as_str());
+            let unit = metric.unit();
 
             result.push_str(&self.render_metric(
                 &metric.name,
@@ -522,8 +866,8 @@ impl Renderer for TextRenderer {
                 vec![
                     trace.name.clone(),
                     format!("{} ms", trace.duration_ms),
-                    trace.start_time.to_rfc3339(),
-                    status,
+                    self.display_timestamp(&trace.start_time.to_rfc3339()),
+                    trace_status(&status, trace.duration_ms, self.slow_threshold_ms).to_string(),
                 ]
             })
             .collect();
@@ -538,8 +882,8 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::models::{LogEntry, LogLevel, Metric, Trace};
-    use crate::templating::renderer::{Block, Renderer, TemplateData};
-    use crate::templating::text_renderer::TextRenderer;
+    use crate::templating::renderer::{Block, MetricThresholds, Renderer, TemplateData};
+    use crate::templating::text_renderer::{BoxStyle, TextRenderer};
 
     fn contains(haystack: &str, needle: &str) -> bool {
         haystack.contains(needle)
@@ -622,6 +966,149 @@ mod tests {
         assert!(!contains(&result_without_title, "Frame Title"));
     }
 
+    #[test]
+    fn test_render_definition_list() {
+        let renderer = TextRenderer::new();
+        let items = vec![
+            ("Host".to_string(), "server-1".to_string()),
+            ("Kernel version".to_string(), "6.1.0".to_string()),
+            ("OS".to_string(), "Debian".to_string()),
+        ];
+
+        let result = renderer.render_definition_list(&items).unwrap();
+        let lines: Vec<&str> = result.lines().filter(|l| !l.is_empty()).collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "Host:           server-1");
+        assert_eq!(lines[1], "Kernel version: 6.1.0");
+        assert_eq!(lines[2], "OS:             Debian");
+    }
+
+    #[test]
+    fn test_render_log_entry_source_tag() {
+        let renderer = TextRenderer::new().with_source_tag("database", "DB");
+
+        let db_log = renderer
+            .render_log_entry("Connected", "INFO", None, Some("database"))
+            .unwrap();
+        assert_eq!(db_log, "[INFO ] [DB] Connected\n");
+
+        let api_log = renderer
+            .render_log_entry("GET /api/users", "INFO", None, Some("api"))
+            .unwrap();
+        assert_eq!(api_log, "[INFO ] [api] GET /api/users\n");
+    }
+
+    #[test]
+    fn test_box_styles() {
+        let headers = vec!["Name".to_string()];
+        let rows = vec![vec!["CPU".to_string()]];
+
+        let rounded = TextRenderer::new()
+            .with_box_style(BoxStyle::Rounded)
+            .render_table(&headers, &rows)
+            .unwrap();
+        assert!(rounded.starts_with('╭'));
+        assert!(rounded.contains('╯'));
+        assert!(rounded.contains('│'));
+
+        let double = TextRenderer::new()
+            .with_box_style(BoxStyle::Double)
+            .render_table(&headers, &rows)
+            .unwrap();
+        assert!(double.starts_with('╔'));
+        assert!(double.contains('║'));
+
+        let forced_ascii = TextRenderer::new()
+            .with_box_style(BoxStyle::Double)
+            .with_ascii_only(true)
+            .render_table(&headers, &rows)
+            .unwrap();
+        assert!(forced_ascii.is_ascii());
+    }
+
+    #[test]
+    fn test_render_jagged_table() {
+        let renderer = TextRenderer::new().with_ascii_only(true);
+        let headers = vec!["Name".to_string(), "Value".to_string()];
+        let rows = vec![
+            vec!["CPU".to_string(), "78.5".to_string(), "%".to_string()],
+            vec!["Memory".to_string()],
+        ];
+
+        let result = renderer.render_table(&headers, &rows).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+
+        let width = lines[0].chars().count();
+        assert!(
+            lines.iter().all(|l| l.chars().count() == width),
+            "{}",
+            result
+        );
+        assert!(lines.iter().all(|l| l.matches(['|', '+']).count() == 4));
+        assert_eq!(lines[1], "| Name   | Value |   |");
+        assert_eq!(lines[5], "| Memory |       |   |");
+    }
+
+    #[test]
+    fn test_render_single_column_table() {
+        let renderer = TextRenderer::new().with_ascii_only(true);
+        let headers = vec!["Host".to_string()];
+        let rows = vec![vec!["server-1".to_string()], vec!["server-2".to_string()]];
+
+        let result = renderer.render_table(&headers, &rows).unwrap();
+
+        assert_eq!(
+            result,
+            "+----------+\n\
+             | Host     |\n\
+             +----------+\n\
+             | server-1 |\n\
+             +----------+\n\
+             | server-2 |\n\
+             +----------+\n"
+        );
+
+        assert_eq!(renderer.render_table(&[], &[vec![]]).unwrap(), "");
+    }
+
+    #[test]
+    fn test_render_nested_frames() {
+        let renderer = TextRenderer::new().with_width(60);
+
+        let block = Block::Frame {
+            id: None,
+            title: Some("Outer".to_string()),
+            content: vec![Block::Frame {
+                id: None,
+                title: Some("Inner".to_string()),
+                content: vec![Block::Metric {
+                    name: "CPU Usage".to_string(),
+                    value: "78.5".to_string(),
+                    unit: Some("%".to_string()),
+                    trend: None,
+                    thresholds: MetricThresholds::default(),
+                }],
+            }],
+        };
+
+        let result = renderer.render_block(&block).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+
+        assert!(lines[0].starts_with("┌ Outer "));
+        let inner_top = lines.iter().find(|l| l.contains("Inner")).unwrap();
+        assert!(inner_top.starts_with("│ ┌ Inner "), "{}", inner_top);
+
+        let outer_width = lines[0].chars().count();
+        assert!(outer_width <= 60);
+        assert!(lines.iter().all(|l| l.chars().count() == outer_width));
+        assert!(lines
+            .iter()
+            .skip(1)
+            .take(lines.len() - 2)
+            .all(|l| l.starts_with("│ ") && l.ends_with('│')));
+    }
+
     #[test]
     fn test_render_metric() {
         let renderer = TextRenderer::new();
@@ -750,6 +1237,32 @@ mod tests {
         assert!(contains(&result, "GET"));
     }
 
+    #[test]
+    fn test_render_trace_metadata_is_deterministic() {
+        let renderer = TextRenderer::new();
+        let render = || {
+            let metadata: HashMap<String, String> = [
+                ("status", "200"),
+                ("endpoint", "/api/users"),
+                ("method", "GET"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+            renderer
+                .render_trace("API Request", 157, "2025-03-15T12:34:56Z", "200", &metadata)
+                .unwrap()
+        };
+
+        let first = render();
+        assert_eq!(first, render());
+
+        let endpoint = first.find("endpoint").unwrap();
+        let method = first.find("method").unwrap();
+        let status = first.rfind("status").unwrap();
+        assert!(endpoint < method && method < status);
+    }
+
     #[test]
     fn test_render_block() {
         let renderer = TextRenderer::new();
@@ -808,6 +1321,8 @@ mod tests {
                 Block::Paragraph("System Status".to_string()),
             ],
             template_name: "dashboard".to_string(),
+            body_classes: Vec::new(),
+            title: None,
         };
 
         let result = renderer.render_template(&template_data).unwrap();
@@ -837,12 +1352,14 @@ mod tests {
                 value: 85.5,
                 timestamp: now,
                 labels: labels1,
+                unit: None,
             },
             Metric {
                 name: "Memory Usage".to_string(),
                 value: 1024.0,
                 timestamp: now,
                 labels: labels2,
+                unit: None,
             },
         ];
 
@@ -857,6 +1374,19 @@ mod tests {
         assert!(contains(&result, "▼"));
     }
 
+    #[test]
+    fn test_render_metrics_trend_keywords() {
+        let renderer = TextRenderer::new();
+
+        let rising = vec![Metric::new("Requests", 120.0).with_label("trend", "up")];
+        assert!(contains(&renderer.render_metrics(&rising).unwrap(), "▲"));
+
+        let unknown = vec![Metric::new("Requests", 120.0).with_label("trend", "sideways")];
+        let result = renderer.render_metrics(&unknown).unwrap();
+        assert!(!contains(&result, "▲"));
+        assert!(!contains(&result, "▼"));
+    }
+
     #[test]
     fn test_render_logs() {
         let renderer = TextRenderer::new();
@@ -953,4 +1483,222 @@ mod tests {
             assert!(line.len() <= 40);
         }
     }
+
+    #[test]
+    fn test_render_chart_sparkline() {
+        let start = Utc::now();
+        let points: Vec<_> = [0.0, 7.0, 3.5]
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (start + chrono::Duration::seconds(i as i64), *v))
+            .collect();
+
+        let renderer = TextRenderer::new();
+        let result = renderer.render_chart("load", &points).unwrap();
+        assert_eq!(result, "load: ▁█▅ (min 0, max 7)\n\n");
+
+        let ascii = TextRenderer::new().with_ascii_only(true);
+        let result = ascii.render_chart("load", &points).unwrap();
+        assert_eq!(result, "load: _#= (min 0, max 7)\n\n");
+
+        let flat = renderer.render_chart("load", &points[..1]).unwrap();
+        assert_eq!(flat, "load: ▅ (min 0, max 0)\n\n");
+
+        let empty = renderer.render_chart("load", &[]).unwrap();
+        assert_eq!(empty, "load: no data\n\n");
+    }
+
+    #[test]
+    fn test_custom_timestamp_format() {
+        let renderer = TextRenderer::new()
+            .with_timestamp_format("%Y-%m-%d %H:%M:%S%.3f")
+            .unwrap();
+
+        let result = renderer
+            .render_log_entry(
+                "Server started",
+                "INFO",
+                Some("2025-03-15T12:34:56.789123Z"),
+                Some("app"),
+            )
+            .unwrap();
+        assert!(result.starts_with("[2025-03-15 12:34:56.789] [INFO ] [app]"));
+
+        let default = TextRenderer::new()
+            .render_log_entry("x", "INFO", Some("2025-03-15T12:34:56.789123Z"), None)
+            .unwrap();
+        assert!(default.contains("2025-03-15T12:34:56.789123Z"));
+
+        assert!(TextRenderer::new().with_timestamp_format("%Q").is_err());
+    }
+
+    #[test]
+    fn test_render_metric_trend_value() {
+        let renderer = TextRenderer::new().with_width(40);
+        let result = renderer
+            .render_metric("CPU Usage", "85.5", None, Some(2.3))
+            .unwrap();
+        assert!(result.trim_end().ends_with("85.5 ▲"));
+
+        let renderer = renderer.with_trend_value(true);
+        let result = renderer
+            .render_metric("CPU Usage", "85.5", None, Some(2.3))
+            .unwrap();
+        assert!(result.trim_end().ends_with("85.5 ▲ +2.3"));
+
+        let result = renderer
+            .render_metric("Memory", "4.2", Some("GB"), Some(-0.5))
+            .unwrap();
+        assert!(result.trim_end().ends_with("4.2 GB ▼ -0.5"));
+
+        let result = renderer.render_metric("Flat", "1", None, None).unwrap();
+        assert!(result.trim_end().ends_with(" 1"));
+    }
+
+    #[test]
+    fn test_render_trace_max_metadata_items() {
+        let metadata: HashMap<String, String> = (0..10)
+            .map(|i| (format!("key{}", i), format!("value{}", i)))
+            .collect();
+
+        let renderer = TextRenderer::new().with_max_metadata_items(3);
+        let result = renderer
+            .render_trace("API Request", 157, "2025-03-15T12:34:56Z", "200", &metadata)
+            .unwrap();
+
+        assert!(result.contains("  key0: value0\n  key1: value1\n  key2: value2\n  (+7 more)\n"));
+        assert!(!result.contains("key3"));
+
+        let unlimited = TextRenderer::new()
+            .render_trace("API Request", 157, "2025-03-15T12:34:56Z", "200", &metadata)
+            .unwrap();
+        assert!(unlimited.contains("key9: value9"));
+        assert!(!unlimited.contains("more)"));
+    }
+
+    #[test]
+    fn test_relative_time() {
+        let now: chrono::DateTime<Utc> = "2025-03-15T12:00:00Z".parse().unwrap();
+        let log = LogEntry::with_timestamp(
+            "Cache miss",
+            LogLevel::Warning,
+            "cache",
+            now - chrono::Duration::seconds(90),
+        );
+
+        let renderer = TextRenderer::new().with_relative_time(true).with_now(now);
+        let result = renderer.render_logs(&[log]).unwrap();
+        assert!(result.starts_with("[1m ago] [WARN ] [cache]"));
+
+        let old = LogEntry::with_timestamp(
+            "Cache miss",
+            LogLevel::Warning,
+            "cache",
+            now - chrono::Duration::days(2),
+        );
+        let result = renderer.render_logs(&[old]).unwrap();
+        assert!(result.contains("2025-03-13T12:00:00"));
+    }
+
+    #[test]
+    fn test_custom_header_and_footer() {
+        let template_data = TemplateData {
+            blocks: vec![Block::Paragraph("Body".to_string())],
+            template_name: "status".to_string(),
+            body_classes: Vec::new(),
+            title: None,
+        };
+        let now: chrono::DateTime<Utc> = "2025-03-15T12:00:00Z".parse().unwrap();
+
+        let default = TextRenderer::new()
+            .with_now(now)
+            .render_template(&template_data)
+            .unwrap();
+        assert!(default.starts_with("# status\n\n"));
+        assert!(default.ends_with("\n--- Generated at 2025-03-15T12:00:00+00:00 ---\n"));
+
+        let custom = TextRenderer::new()
+            .with_now(now)
+            .with_header_format(None)
+            .with_footer_format(Some("\n[{name} @ {timestamp}]\n"))
+            .render_template(&template_data)
+            .unwrap();
+        assert_eq!(custom, "Body\n\n\n[status @ 2025-03-15T12:00:00+00:00]\n");
+    }
+
+    #[test]
+    fn test_render_metric_thresholds() {
+        let template = crate::templating::Template::from_string(
+            "alerts",
+            "@metric{CPU}{95}{%}{+2}{warn:80,crit:90}\n@metric{Memory}{85}{%}{0}{warn:80,crit:90}",
+        )
+        .unwrap();
+        let result = TextRenderer::new().render_blocks(&template.blocks).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+
+        assert!(lines[0].starts_with("CPU:") && lines[0].ends_with("95 % ▲ !!"));
+        assert!(lines[1].starts_with("Memory:") && lines[1].ends_with("85 % !"));
+
+        let thresholds = MetricThresholds {
+            warn: None,
+            crit: Some(90.0),
+        };
+        let rounded = TextRenderer::new()
+            .with_value_precision(Some(0))
+            .render_metric_with_thresholds("CPU", "90.4", Some("%"), None, &thresholds)
+            .unwrap();
+        assert!(rounded.trim_end().ends_with("90 % !!"));
+    }
+
+    #[test]
+    fn test_value_precision() {
+        let fixed = TextRenderer::new()
+            .with_value_precision(Some(2))
+            .render_metric("CPU Usage", "78.5", Some("%"), None)
+            .unwrap();
+        assert!(fixed.contains("78.50 %"));
+
+        let natural = TextRenderer::new()
+            .render_metric("CPU Usage", "78.50000000000001", Some("%"), None)
+            .unwrap();
+        assert!(natural.contains("78.5 %"));
+        assert!(!natural.contains("78.50"));
+    }
+
+    #[test]
+    fn test_code_line_numbers() {
+        let code = (1..=12)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let result = TextRenderer::new()
+            .with_line_numbers(true)
+            .render_code(&code)
+            .unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+
+        for n in 1..=12 {
+            assert_eq!(lines[n - 1], format!("{:>2} | line {}", n, n));
+        }
+        assert_eq!(lines[0], " 1 | line 1");
+
+        let plain = TextRenderer::new().render_code(&code).unwrap();
+        assert!(plain.starts_with("line 1\n"));
+    }
+
+    #[test]
+    fn test_render_byte_metrics() {
+        let renderer = TextRenderer::new();
+
+        let bytes = renderer
+            .render_metric("Heap", "1536", Some("bytes"), None)
+            .unwrap();
+        assert!(bytes.contains("1.5 KiB"));
+        assert!(!bytes.contains("1536"));
+
+        let other = renderer
+            .render_metric("Memory", "1536", Some("MB"), None)
+            .unwrap();
+        assert!(other.contains("1536 MB"));
+    }
 }